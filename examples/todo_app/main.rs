@@ -18,13 +18,8 @@ use bevy::{
 /* TODO:
     - double container (checked/unchecked)
     - separate root to disable ui
-    - checkbox
-    - radio button
-    - tab navigation
-    - improve InputBox (move caret to pointer on click, blinking caret, Ctrl+Arrows, Crtl+Backspace, selection)
     - clear input button
     - styling
-    - copy/paste support?
     - derive MapEntity?
 */
 
@@ -49,6 +44,8 @@ fn main() {
         .add_system(input_box_event)
         .add_system(event_button::<ListButtonEvent>)
         .add_system(list_status_event)
+        .add_system(update_clear_button)
+        .add_system(check_item)
         .add_startup_system(setup_ui_camera)
         .add_startup_system(spawn_ui)
         .add_system(
@@ -21,7 +21,11 @@ pub fn build_todo_item(
     let mut button = None;
     let mut row = None;
     Stack::new_row((
-        // TODO: checkbox
+        // Checkbox to complete the item
+        CheckBox::new(&wb).get_mut(|button_material: &mut components::ButtonMaterial| {
+            button_material.material = colors.add(Color::NONE.into());
+            button_material.material_hovered = colors.add(Color::BLUE.into());
+        }),
         // Label of the todo item
         Label::new(&wb, "something")
             .set_font_color(Color::WHITE)
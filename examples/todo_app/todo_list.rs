@@ -51,6 +51,9 @@ pub fn build_todo_list(
             .set_font_color(Color::WHITE),
         // Input
         InputBox::new(&wb)
+            .placeholder("What needs to be done?")
+            // Empty items are rejected with an `InputBoxInvalidEvent`
+            .insert(components::InputValidation::required())
             // Save its entity to identify it later
             .set_root_id(&mut input),
         // List of unchecked items
@@ -141,16 +144,18 @@ pub fn build_todo_list(
                 .get_mut(|style: &mut Style| {
                     style.flex_grow = 1.;
                 }),
-            // Button to delete all completed items
+            // Button to delete all completed items, disabled while there are none
             Button::new(
                 Label::new(&wb, "Clear completed")
                     .set_font_color(Color::WHITE)
-                    .set_font_size(16.),
+                    .set_font_size(16.)
+                    .insert(DisabledColor::new(Color::GRAY)),
             )
             .set_root_id(&mut button_clear)
             .get_mut(|button_material: &mut components::ButtonMaterial| {
                 button_material.material = colors.add(Color::GRAY.into());
                 button_material.material_hovered = colors.add(Color::BLUE.into());
+                button_material.material_disabled = colors.add(Color::DARK_GRAY.into());
             })
             .get_mut(|style: &mut Style| {
                 style.flex_grow = 1.;
@@ -194,6 +199,7 @@ pub fn build_todo_list(
         list_unchecked: list_unchecked.unwrap(),
         hidden_unchecked: hidden_unchecked.unwrap(),
         hidden_checked: hidden_checked.unwrap(),
+        button_clear: button_clear.unwrap(),
     })
     .set_root_id(&mut root);
 
@@ -243,6 +249,7 @@ pub struct TodoList {
     pub hidden_unchecked: Entity,
     pub list_checked: Entity,
     pub list_unchecked: Entity,
+    pub button_clear: Entity,
 }
 
 // This is a fix for #1395
@@ -253,6 +260,7 @@ impl FromWorld for TodoList {
             hidden_unchecked: Entity::new(u32::MAX),
             list_checked: Entity::new(u32::MAX),
             list_unchecked: Entity::new(u32::MAX),
+            button_clear: Entity::new(u32::MAX),
         }
     }
 }
@@ -264,6 +272,7 @@ impl MapEntities for TodoList {
         self.hidden_unchecked = entity_map.get(self.hidden_unchecked)?;
         self.list_checked = entity_map.get(self.list_checked)?;
         self.list_unchecked = entity_map.get(self.list_unchecked)?;
+        self.button_clear = entity_map.get(self.button_clear)?;
         Ok(())
     }
 }
@@ -296,14 +305,12 @@ impl MapEntities for TodoInput {
 pub fn input_box_event(
     mut return_event_reader: EventReader<InputBoxReturnEvent>,
     mut clear_event_writer: EventWriter<InputBoxClearEvent>,
+    mut clear_history_event_writer: EventWriter<InputBoxClearHistoryEvent>,
     query_input: Query<&TodoInput>,
     mut cmd: Commands,
     mut scenes: ResMut<Assets<Scene>>,
 ) {
     for event in return_event_reader.iter() {
-        if event.text.is_empty() {
-            continue;
-        }
         if let Ok(input) = query_input.get(event.source) {
             // Spawn a new todo item from the template (`input.scene`) as a child of the target entity
             // FIXME: this only works for one per stage since the scene is spawned when commands are applied
@@ -325,6 +332,9 @@ pub fn input_box_event(
                 clear_event_writer.send(InputBoxClearEvent {
                     target: event.source,
                 });
+                clear_history_event_writer.send(InputBoxClearHistoryEvent {
+                    target: event.source,
+                });
             });
         }
     }
@@ -417,3 +427,56 @@ pub fn list_status_event(
         }
     }
 }
+
+// Disable the "Clear completed" button when there are no completed items
+pub fn update_clear_button(
+    mut commands: Commands,
+    query_list: Query<&TodoList>,
+    query_children: Query<&Children>,
+    query_disabled: Query<Entity, With<Disabled>>,
+) {
+    for todo_list in query_list.iter() {
+        let has_checked = query_children
+            .get(todo_list.list_checked)
+            .map_or(false, |children| !children.is_empty());
+        let disabled = query_disabled.get(todo_list.button_clear).is_ok();
+        if has_checked && disabled {
+            commands.entity(todo_list.button_clear).remove::<Disabled>();
+        } else if !has_checked && !disabled {
+            commands.entity(todo_list.button_clear).insert(Disabled);
+        }
+    }
+}
+
+// Move todo items between the checked and unchecked lists when their checkbox is toggled
+pub fn check_item(
+    mut commands: Commands,
+    mut event_reader: EventReader<CheckBoxChanged>,
+    query_list: Query<&TodoList>,
+    query_parent: Query<&Parent>,
+    mut query_children: Query<&mut Children>,
+) {
+    for event in event_reader.iter() {
+        // The checkbox is in the item's row, which is in a list
+        let row = query_parent.get(event.source).unwrap().0;
+        let list = query_parent.get(row).unwrap().0;
+        for todo_list in query_list.iter() {
+            let target = if event.checked && list == todo_list.list_unchecked {
+                todo_list.list_checked
+            } else if !event.checked && list == todo_list.list_checked {
+                todo_list.list_unchecked
+            } else {
+                continue;
+            };
+            // `push_children` does not remove the row from its previous parent
+            let mut children = query_children.get_mut(list).unwrap();
+            let remaining = children
+                .iter()
+                .copied()
+                .filter(|child| *child != row)
+                .collect::<Vec<_>>();
+            *children = Children::with(&remaining);
+            commands.entity(target).push_children(&[row]);
+        }
+    }
+}
@@ -0,0 +1,79 @@
+/// A backend for the `Clipboard` resource
+pub trait ClipboardProvider: Send + Sync + 'static {
+    fn get_contents(&mut self) -> Option<String>;
+    fn set_contents(&mut self, contents: String);
+}
+
+/// Resource used by the widgets to copy and paste text.
+/// Insert it before adding the `WidgetsPlugin` to use a custom `ClipboardProvider`,
+/// otherwise the system clipboard is used if the `system_clipboard` feature is enabled,
+/// and a `MemoryClipboard` if it is not or if the system clipboard is unavailable.
+pub struct Clipboard(pub Box<dyn ClipboardProvider>);
+
+impl Clipboard {
+    pub fn new(provider: impl ClipboardProvider) -> Self {
+        Self(Box::new(provider))
+    }
+
+    pub fn get_contents(&mut self) -> Option<String> {
+        self.0.get_contents()
+    }
+
+    pub fn set_contents(&mut self, contents: String) {
+        self.0.set_contents(contents)
+    }
+}
+
+impl Default for Clipboard {
+    fn default() -> Self {
+        #[cfg(feature = "system_clipboard")]
+        if let Some(system) = SystemClipboard::new() {
+            return Self::new(system);
+        }
+        Self::new(MemoryClipboard::default())
+    }
+}
+
+/// Keeps the clipboard contents in memory, for tests and headless runs
+#[derive(Default)]
+pub struct MemoryClipboard(pub Option<String>);
+
+impl ClipboardProvider for MemoryClipboard {
+    fn get_contents(&mut self) -> Option<String> {
+        self.0.clone()
+    }
+
+    fn set_contents(&mut self, contents: String) {
+        self.0 = Some(contents);
+    }
+}
+
+/// Uses the clipboard of the operating system
+#[cfg(feature = "system_clipboard")]
+pub struct SystemClipboard(std::sync::Mutex<arboard::Clipboard>);
+
+#[cfg(feature = "system_clipboard")]
+impl SystemClipboard {
+    pub fn new() -> Option<Self> {
+        match arboard::Clipboard::new() {
+            Ok(clipboard) => Some(Self(std::sync::Mutex::new(clipboard))),
+            Err(error) => {
+                bevy::log::warn!("System clipboard unavailable: {}", error);
+                None
+            }
+        }
+    }
+}
+
+#[cfg(feature = "system_clipboard")]
+impl ClipboardProvider for SystemClipboard {
+    fn get_contents(&mut self) -> Option<String> {
+        self.0.get_mut().unwrap().get_text().ok()
+    }
+
+    fn set_contents(&mut self, contents: String) {
+        if let Err(error) = self.0.get_mut().unwrap().set_text(contents) {
+            bevy::log::warn!("Failed to copy to the system clipboard: {}", error);
+        }
+    }
+}
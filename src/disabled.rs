@@ -0,0 +1,65 @@
+use bevy::prelude::*;
+
+/// Disables a widget and all its descendants: they cannot be focused or interacted with,
+/// do not send events and use `VisualState::Disabled`
+#[derive(Reflect, Default, Component)]
+#[reflect(Component)]
+pub struct Disabled;
+
+/// Inserted by `propagate_disabled` on the entities that are `Disabled` or have a `Disabled` ancestor.
+/// Systems should check this component instead of `Disabled`.
+#[derive(Reflect, Default, Component)]
+#[reflect(Component)]
+pub struct InheritedDisabled;
+
+pub fn propagate_disabled(
+    mut cmd: Commands,
+    query_roots: Query<Entity, (With<Node>, Without<Parent>)>,
+    query_children: Query<&Children>,
+    query_disabled: Query<(Option<&Disabled>, Option<&InheritedDisabled>)>,
+) {
+    for root in query_roots.iter() {
+        propagate_recursive(root, false, &mut cmd, &query_children, &query_disabled);
+    }
+}
+
+fn propagate_recursive(
+    entity: Entity,
+    parent_disabled: bool,
+    cmd: &mut Commands,
+    query_children: &Query<&Children>,
+    query_disabled: &Query<(Option<&Disabled>, Option<&InheritedDisabled>)>,
+) {
+    let disabled = if let Ok((disabled, inherited)) = query_disabled.get(entity) {
+        let disabled = parent_disabled || disabled.is_some();
+        match (disabled, inherited.is_some()) {
+            (true, false) => {
+                cmd.entity(entity).insert(InheritedDisabled);
+            }
+            (false, true) => {
+                cmd.entity(entity).remove::<InheritedDisabled>();
+            }
+            _ => {}
+        }
+        disabled
+    } else {
+        parent_disabled
+    };
+    if let Ok(children) = query_children.get(entity) {
+        for child in children.iter() {
+            propagate_recursive(*child, disabled, cmd, query_children, query_disabled);
+        }
+    }
+}
+
+/// Cancels the `Interaction` that `bevy::ui` sets on disabled entities,
+/// so that systems watching `Interaction::Clicked` ignore them
+pub fn reset_disabled_interaction(
+    mut query: Query<&mut Interaction, (With<InheritedDisabled>, Changed<Interaction>)>,
+) {
+    for mut interaction in query.iter_mut() {
+        if *interaction != Interaction::None {
+            *interaction = Interaction::None;
+        }
+    }
+}
@@ -1,15 +1,26 @@
 use bevy::{
+    app::Events,
+    ecs::system::Command,
     input::{keyboard::KeyboardInput, ElementState},
     prelude::*,
 };
 use serde::{Deserialize, Serialize};
+
+use crate::{widgets::components::InputBox, InheritedDisabled};
 /*
 - UI entities can be Focusable
 - at most one entity can be focused, only it is allowed to process keyboard events
 - the focused entity should have a visual indicator
 - Interaction::Clicked sets focus to that entity
-- hitting Tab (Shift+Tab) moves focus to the next (previous) entity: children, or sibling, or parent's sibling, etc. If there is previous focus, focus the 1st entity.
+- hitting Tab (Shift+Tab) moves focus to the next (previous) entity: children, or sibling, or parent's sibling, etc. If there is no previous focus, focus the 1st (last) entity. Focus wraps around.
 - hitting Esc removes focus
+- the focused entity can keep Tab, Esc and the arrow keys for itself with `KeyCapture` (e.g. while a popup is open)
+- the Tab order can be overridden with `TabIndex`, and limited to a subtree with a trapping `FocusScope`
+- focus is changed programmatically with `WorldFocusExt`, `FocusCommand` or `FocusRequestEvent`, never by setting `Focusable` directly
+- disabled entities (see `Disabled`) cannot be focused, and lose focus when they get disabled
+- arrow keys and the gamepad D-pad move focus to the nearest entity in that direction, inside a directional `FocusScope` (a focused `InputBox` keeps the arrow keys)
+- every change of focus sends `FocusLost` and `FocusGained` events, with the cause of the change
+- the focus is removed when the focused entity is despawned, and entities spawned `Focused` (e.g. from a saved scene) are unfocused
 */
 
 /// Resource
@@ -28,29 +39,138 @@ impl Default for Focusable {
     }
 }
 
+/// What caused a change of focus
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FocusCause {
+    Mouse,
+    Tab,
+    Escape,
+    Directional,
+    Programmatic,
+}
+
+/// Sent when an entity becomes the `CurrentFocus`
+pub struct FocusGained {
+    pub entity: Entity,
+    pub cause: FocusCause,
+}
+
+/// Sent when an entity stops being the `CurrentFocus`, before the matching `FocusGained`
+pub struct FocusLost {
+    pub entity: Entity,
+    pub cause: FocusCause,
+}
+
+/// Send this event to focus an entity, or to remove focus with `None`
+pub struct FocusRequestEvent {
+    pub target: Option<Entity>,
+}
+
+/// Overrides the position of a `Focusable` entity in the Tab order.
+/// Entities with a positive index come first, by increasing index, followed by the entities
+/// with a zero index (or without `TabIndex`) in document order.
+/// Entities with a negative index are skipped by Tab, but can still be focused by the mouse.
+#[derive(Reflect, Default, Clone, Copy, Component)]
+#[reflect(Component)]
+pub struct TabIndex(pub i32);
+
+/// Groups the `Focusable` descendants of an entity.
+/// When `trap` is set and the focus is inside the scope, Tab and Shift+Tab cycle through
+/// the scope only (e.g. for a modal dialog).
+/// When `directional` is set, arrow keys and the gamepad D-pad move the focus between the entities
+/// of the scope. Only the closest scope of the focused entity is considered. A focused `InputBox`
+/// keeps the arrow keys to move its caret, but the D-pad still moves the focus out of it.
+#[derive(Reflect, Default, Clone, Copy, Component)]
+#[reflect(Component)]
+pub struct FocusScope {
+    pub trap: bool,
+    pub directional: bool,
+}
+
+/// Keys that the focused entity handles itself instead of the focus navigation, e.g. while it shows
+/// a popup. `Enter` is not used by the navigation, but tells widgets that it is already handled.
+#[derive(Reflect, Default, Clone, Copy, PartialEq, Eq, Debug, Component)]
+#[reflect(Component)]
+pub struct KeyCapture {
+    pub tab: bool,
+    pub escape: bool,
+    pub enter: bool,
+    pub arrows: bool,
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn tab_navigation(
     mut keyboard_input_events: EventReader<KeyboardInput>,
     keyboard_state: Res<Input<KeyCode>>,
     mut current_focus: ResMut<CurrentFocus>,
-    mut query_focusable: Query<&mut Focusable>,
+    mut query_focusable: Query<(&mut Focusable, Option<&InheritedDisabled>)>,
+    mut focus_lost: EventWriter<FocusLost>,
+    mut focus_gained: EventWriter<FocusGained>,
+    query_candidates: Query<Option<&TabIndex>, (With<Focusable>, Without<InheritedDisabled>)>,
+    query_roots: Query<Entity, (With<Node>, Without<Parent>)>,
+    query_children: Query<&Children>,
+    query_parent: Query<&Parent>,
+    query_scope: Query<&FocusScope>,
+    query_capture: Query<&KeyCapture>,
 ) {
     for event in keyboard_input_events.iter() {
         if let Some(key_code) = event.key_code {
             if event.state == ElementState::Pressed {
+                let capture = current_focus
+                    .0
+                    .and_then(|focus| query_capture.get(focus).ok())
+                    .copied()
+                    .unwrap_or_default();
                 match key_code {
-                    KeyCode::Tab => {
-                        if keyboard_state.pressed(KeyCode::LShift) {
-                            info!("focus prev");
+                    KeyCode::Tab if !capture.tab => {
+                        let reverse = keyboard_state.pressed(KeyCode::LShift)
+                            || keyboard_state.pressed(KeyCode::RShift);
+                        let trap = current_focus.0.and_then(|focus| {
+                            find_scope(focus, &query_parent, &query_scope, |scope| scope.trap)
+                        });
+                        let roots = if let Some((trap, _)) = trap {
+                            vec![trap]
                         } else {
-                            info!("focus next");
+                            let mut roots = query_roots.iter().collect::<Vec<_>>();
+                            // Query order is not stable, sort the roots to keep the order consistent
+                            roots.sort();
+                            roots
+                        };
+                        let mut order = Vec::new();
+                        for root in roots {
+                            collect_focusable(
+                                root,
+                                &|entity| query_children.get(entity).ok(),
+                                &|entity| {
+                                    query_candidates.get(entity).ok().map(|tab_index| {
+                                        tab_index.map_or(0, |tab_index| tab_index.0)
+                                    })
+                                },
+                                &mut order,
+                            );
+                        }
+                        let order = sort_tab_order(order);
+                        if let Some(next) = step_focus(&order, current_focus.0, reverse) {
+                            set_focus(
+                                Some(next),
+                                FocusCause::Tab,
+                                &mut current_focus,
+                                &mut query_focusable,
+                                &mut focus_lost,
+                                &mut focus_gained,
+                            );
                         }
                     }
-                    KeyCode::Escape => {
+                    KeyCode::Escape if !capture.escape => {
                         info!("unfocus");
-                        if let Some(previous_focus) = current_focus.0.take() {
-                            *query_focusable.get_mut(previous_focus).unwrap() =
-                                Focusable::Unfocused;
-                        }
+                        set_focus(
+                            None,
+                            FocusCause::Escape,
+                            &mut current_focus,
+                            &mut query_focusable,
+                            &mut focus_lost,
+                            &mut focus_gained,
+                        );
                     }
                     _ => {}
                 }
@@ -59,51 +179,458 @@ pub fn tab_navigation(
     }
 }
 
-#[derive(Reflect, Default, Component)]
-#[reflect(Component)]
-pub struct FocusMaterial {
-    material: Handle<ColorMaterial>,
-    #[reflect(ignore)]
-    cache: Option<Handle<ColorMaterial>>,
+/// Returns the closest ancestor of `entity` (or `entity` itself) with a `FocusScope` matching `filter`.
+fn find_scope(
+    entity: Entity,
+    query_parent: &Query<&Parent>,
+    query_scope: &Query<&FocusScope>,
+    filter: impl Fn(&FocusScope) -> bool,
+) -> Option<(Entity, FocusScope)> {
+    let mut current = Some(entity);
+    while let Some(entity) = current {
+        if let Ok(scope) = query_scope.get(entity) {
+            if filter(scope) {
+                return Some((entity, *scope));
+            }
+        }
+        current = query_parent.get(entity).ok().map(|parent| parent.0);
+    }
+    None
 }
 
-pub fn focus_material(
-    mut query: Query<
-        (&mut FocusMaterial, &mut Handle<ColorMaterial>, &Focusable),
-        Or<(Changed<FocusMaterial>, Changed<Focusable>)>,
+#[allow(clippy::too_many_arguments)]
+pub fn directional_navigation(
+    keyboard_input: Res<Input<KeyCode>>,
+    gamepad_input: Res<Input<GamepadButton>>,
+    mut current_focus: ResMut<CurrentFocus>,
+    mut query_focusable: Query<(&mut Focusable, Option<&InheritedDisabled>)>,
+    mut focus_lost: EventWriter<FocusLost>,
+    mut focus_gained: EventWriter<FocusGained>,
+    query_candidates: Query<
+        (&Node, &GlobalTransform),
+        (With<Focusable>, Without<InheritedDisabled>),
     >,
+    query_children: Query<&Children>,
+    query_parent: Query<&Parent>,
+    query_scope: Query<&FocusScope>,
+    query_capture: Query<&KeyCapture>,
+    query_input_box: Query<&InputBox>,
 ) {
-    // TODO: handle Interaction
-    for (mut focus_material, mut material, focusable) in query.iter_mut() {
-        match focusable {
-            Focusable::Focused => {
-                focus_material.cache = Some(material.clone());
-                *material = focus_material.material.clone();
+    let focus = if let Some(focus) = current_focus.0 {
+        focus
+    } else {
+        return;
+    };
+    // The arrow keys move the caret of a focused `InputBox`
+    let text_input = query_input_box.get(focus).is_ok();
+    let key_pressed = |key_code| !text_input && keyboard_input.just_pressed(key_code);
+    let gamepad_pressed = |button_type| {
+        gamepad_input
+            .get_just_pressed()
+            .any(|button| button.1 == button_type)
+    };
+    let direction = if key_pressed(KeyCode::Up) || gamepad_pressed(GamepadButtonType::DPadUp) {
+        Vec2::new(0., 1.)
+    } else if key_pressed(KeyCode::Down) || gamepad_pressed(GamepadButtonType::DPadDown) {
+        Vec2::new(0., -1.)
+    } else if key_pressed(KeyCode::Left) || gamepad_pressed(GamepadButtonType::DPadLeft) {
+        Vec2::new(-1., 0.)
+    } else if key_pressed(KeyCode::Right) || gamepad_pressed(GamepadButtonType::DPadRight) {
+        Vec2::new(1., 0.)
+    } else {
+        return;
+    };
+    if query_capture
+        .get(focus)
+        .map_or(false, |capture| capture.arrows)
+    {
+        return;
+    }
+    let scope = match find_scope(focus, &query_parent, &query_scope, |_| true) {
+        Some((scope, FocusScope { directional, .. })) if directional => scope,
+        _ => return,
+    };
+    let origin = if let Ok((_, transform)) = query_candidates.get(focus) {
+        transform.translation.truncate()
+    } else {
+        return;
+    };
+    let mut candidates = Vec::new();
+    collect_focusable(
+        scope,
+        &|entity| query_children.get(entity).ok(),
+        &|entity| query_candidates.get(entity).ok().map(|_| 0),
+        &mut candidates,
+    );
+    let next = candidates
+        .into_iter()
+        .filter(|(entity, _)| *entity != focus)
+        .filter_map(|(entity, _)| {
+            let (node, transform) = query_candidates.get(entity).ok()?;
+            directional_score(
+                origin,
+                transform.translation.truncate(),
+                node.size,
+                direction,
+            )
+            .map(|score| (entity, score))
+        })
+        .min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())
+        .map(|(entity, _)| entity);
+    if let Some(next) = next {
+        set_focus(
+            Some(next),
+            FocusCause::Directional,
+            &mut current_focus,
+            &mut query_focusable,
+            &mut focus_lost,
+            &mut focus_gained,
+        );
+    }
+}
+
+/// Scores a candidate node for directional navigation from `origin`, lower is better.
+/// Returns `None` if the candidate is not in the given direction.
+fn directional_score(origin: Vec2, position: Vec2, size: Vec2, direction: Vec2) -> Option<f32> {
+    // Use the point of the candidate that is the closest to the origin
+    let half_size = size / 2.;
+    let closest = origin.max(position - half_size).min(position + half_size);
+    let delta = closest - origin;
+    let along = delta.dot(direction);
+    if along <= 0. {
+        return None;
+    }
+    // Favor nodes that are aligned with the origin
+    let across = (delta - along * direction).length();
+    Some(along + 2. * across)
+}
+
+/// Pushes the `Focusable` entities of the tree starting at `entity` in document order,
+/// with their `TabIndex`: an entity comes before its children, which come before its next sibling.
+/// `get_tab_index` returns `None` for entities that are not `Focusable`.
+fn collect_focusable<'a>(
+    entity: Entity,
+    get_children: &impl Fn(Entity) -> Option<&'a Children>,
+    get_tab_index: &impl Fn(Entity) -> Option<i32>,
+    order: &mut Vec<(Entity, i32)>,
+) {
+    if let Some(tab_index) = get_tab_index(entity) {
+        order.push((entity, tab_index));
+    }
+    if let Some(children) = get_children(entity) {
+        for child in children.iter() {
+            collect_focusable(*child, get_children, get_tab_index, order);
+        }
+    }
+}
+
+/// Applies the `TabIndex` rules to entities collected in document order.
+fn sort_tab_order(mut order: Vec<(Entity, i32)>) -> Vec<Entity> {
+    order.retain(|(_, tab_index)| *tab_index >= 0);
+    // The sort is stable, so entities with the same index stay in document order
+    order.sort_by_key(|(_, tab_index)| match *tab_index {
+        0 => i32::MAX,
+        tab_index => tab_index,
+    });
+    order.into_iter().map(|(entity, _)| entity).collect()
+}
+
+/// Returns the entity following (or preceding) `current` in `order`, wrapping around.
+/// Without current focus, the first (or last) entity is returned.
+fn step_focus(order: &[Entity], current: Option<Entity>, reverse: bool) -> Option<Entity> {
+    if order.is_empty() {
+        return None;
+    }
+    let len = order.len();
+    let current = current.and_then(|current| order.iter().position(|e| *e == current));
+    let index = match (current, reverse) {
+        (None, false) => 0,
+        (None, true) => len - 1,
+        (Some(index), false) => (index + 1) % len,
+        (Some(index), true) => (index + len - 1) % len,
+    };
+    Some(order[index])
+}
+
+/// Moves the focus to `target`, keeping `CurrentFocus` and the `Focusable` components in sync,
+/// and sends the focus events.
+/// Targets that are not `Focusable`, or are disabled, are ignored.
+fn set_focus(
+    target: Option<Entity>,
+    cause: FocusCause,
+    current_focus: &mut CurrentFocus,
+    query_focusable: &mut Query<(&mut Focusable, Option<&InheritedDisabled>)>,
+    focus_lost: &mut EventWriter<FocusLost>,
+    focus_gained: &mut EventWriter<FocusGained>,
+) {
+    if current_focus.0 == target {
+        return;
+    }
+    if let Some(entity) = target {
+        match query_focusable.get_mut(entity) {
+            Ok((_, None)) => {}
+            Ok((_, Some(_))) => {
+                warn!("Cannot focus {:?}, it is disabled", entity);
+                return;
             }
-            Focusable::Unfocused => {
-                if let Some(cached) = focus_material.cache.take() {
-                    *material = cached;
-                }
+            Err(_) => {
+                warn!("Cannot focus {:?}, it is not Focusable", entity);
+                return;
             }
         }
     }
+    if let Some(previous_focus) = current_focus.0.take() {
+        if let Ok((mut focusable, _)) = query_focusable.get_mut(previous_focus) {
+            *focusable = Focusable::Unfocused;
+        }
+        focus_lost.send(FocusLost {
+            entity: previous_focus,
+            cause,
+        });
+    }
+    if let Some(entity) = target {
+        *query_focusable.get_mut(entity).unwrap().0 = Focusable::Focused;
+        current_focus.0 = Some(entity);
+        focus_gained.send(FocusGained { entity, cause });
+    }
+}
+
+/// Material used while the entity is focused, see `VisualState`
+#[derive(Reflect, Default, Component)]
+#[reflect(Component)]
+pub struct FocusMaterial {
+    pub material: Handle<ColorMaterial>,
+    // Material to restore when the entity goes back to `VisualState::Normal`,
+    // only used when the entity has no `ButtonMaterial`
+    #[reflect(ignore)]
+    pub(crate) cache: Option<Handle<ColorMaterial>>,
+}
+
+impl FocusMaterial {
+    pub fn new(material: Handle<ColorMaterial>) -> Self {
+        Self {
+            material,
+            cache: None,
+        }
+    }
 }
 
 pub fn mouse_focus(
-    query_interaction: Query<(Entity, &Interaction), (Changed<Interaction>, With<Focusable>)>,
-    mut query_focusable: Query<&mut Focusable>,
+    query_interaction: Query<
+        (Entity, &Interaction),
+        (
+            Changed<Interaction>,
+            With<Focusable>,
+            Without<InheritedDisabled>,
+        ),
+    >,
+    mut query_focusable: Query<(&mut Focusable, Option<&InheritedDisabled>)>,
     mut current_focus: ResMut<CurrentFocus>,
+    mut focus_lost: EventWriter<FocusLost>,
+    mut focus_gained: EventWriter<FocusGained>,
 ) {
     for (entity, interaction) in query_interaction.iter() {
         if matches!(interaction, Interaction::Clicked) {
-            if let Some(previous_focus) = current_focus.0.take() {
-                if let Ok(mut focusable) = query_focusable.get_mut(previous_focus) {
-                    *focusable = Focusable::Unfocused;
+            info!("mouse focus");
+            set_focus(
+                Some(entity),
+                FocusCause::Mouse,
+                &mut current_focus,
+                &mut query_focusable,
+                &mut focus_lost,
+                &mut focus_gained,
+            );
+        }
+    }
+}
+
+/// Keeps `CurrentFocus` and the `Focusable` components consistent when used from exclusive systems
+pub trait WorldFocusExt {
+    /// Focuses `entity`, unfocusing the previously focused entity
+    fn focus(&mut self, entity: Entity);
+    /// Removes focus from the currently focused entity
+    fn clear_focus(&mut self);
+    /// Focuses the first entity in Tab order inside the tree starting at `root`, and returns it
+    fn focus_first_in(&mut self, root: Entity) -> Option<Entity>;
+}
+
+impl WorldFocusExt for World {
+    fn focus(&mut self, entity: Entity) {
+        set_focus_world(self, Some(entity), FocusCause::Programmatic);
+    }
+
+    fn clear_focus(&mut self) {
+        set_focus_world(self, None, FocusCause::Programmatic);
+    }
+
+    fn focus_first_in(&mut self, root: Entity) -> Option<Entity> {
+        let mut order = Vec::new();
+        collect_focusable(
+            root,
+            &|entity| self.get::<Children>(entity),
+            &|entity| {
+                self.get::<Focusable>(entity)?;
+                if self.get::<InheritedDisabled>(entity).is_some() {
+                    return None;
                 }
+                Some(
+                    self.get::<TabIndex>(entity)
+                        .map_or(0, |tab_index| tab_index.0),
+                )
+            },
+            &mut order,
+        );
+        let first = sort_tab_order(order).first().copied();
+        if let Some(first) = first {
+            self.focus(first);
+        }
+        first
+    }
+}
+
+/// `World` counterpart of `set_focus`
+fn set_focus_world(world: &mut World, target: Option<Entity>, cause: FocusCause) {
+    let current = world.get_resource::<CurrentFocus>().unwrap().0;
+    if current == target {
+        return;
+    }
+    if let Some(entity) = target {
+        if world.get::<Focusable>(entity).is_none() {
+            warn!("Cannot focus {:?}, it is not Focusable", entity);
+            return;
+        }
+        if world.get::<InheritedDisabled>(entity).is_some() {
+            warn!("Cannot focus {:?}, it is disabled", entity);
+            return;
+        }
+    }
+    if let Some(previous_focus) = current {
+        if let Some(mut focusable) = world.get_mut::<Focusable>(previous_focus) {
+            *focusable = Focusable::Unfocused;
+        }
+        world
+            .get_resource_mut::<Events<FocusLost>>()
+            .unwrap()
+            .send(FocusLost {
+                entity: previous_focus,
+                cause,
+            });
+    }
+    world.get_resource_mut::<CurrentFocus>().unwrap().0 = target;
+    if let Some(entity) = target {
+        *world.get_mut::<Focusable>(entity).unwrap() = Focusable::Focused;
+        world
+            .get_resource_mut::<Events<FocusGained>>()
+            .unwrap()
+            .send(FocusGained { entity, cause });
+    }
+}
+
+/// Changes the focus when commands are applied, see `WorldFocusExt`.
+/// Use with `Commands::add`.
+pub enum FocusCommand {
+    Focus(Entity),
+    Clear,
+    FocusFirstIn(Entity),
+}
+
+impl Command for FocusCommand {
+    fn write(self, world: &mut World) {
+        match self {
+            FocusCommand::Focus(entity) => world.focus(entity),
+            FocusCommand::Clear => world.clear_focus(),
+            FocusCommand::FocusFirstIn(root) => {
+                world.focus_first_in(root);
             }
-            info!("mouse focus");
-            *query_focusable.get_mut(entity).unwrap() = Focusable::Focused;
-            current_focus.0 = Some(entity);
+        }
+    }
+}
+
+/// Checks that at most one entity is focused, and that it is the `CurrentFocus`
+pub fn check_focus(current_focus: Res<CurrentFocus>, query: Query<(Entity, &Focusable)>) {
+    for (entity, focusable) in query.iter() {
+        match focusable {
+            Focusable::Focused => debug_assert_eq!(
+                current_focus.0,
+                Some(entity),
+                "{:?} is Focused but is not the CurrentFocus, use WorldFocusExt, FocusCommand or FocusRequestEvent to change focus",
+                entity
+            ),
+            Focusable::Unfocused => debug_assert_ne!(
+                current_focus.0,
+                Some(entity),
+                "{:?} is the CurrentFocus but is not Focused",
+                entity
+            ),
+        }
+    }
+}
+
+pub fn focus_request(
+    mut event_reader: EventReader<FocusRequestEvent>,
+    mut query_focusable: Query<(&mut Focusable, Option<&InheritedDisabled>)>,
+    mut current_focus: ResMut<CurrentFocus>,
+    mut focus_lost: EventWriter<FocusLost>,
+    mut focus_gained: EventWriter<FocusGained>,
+) {
+    for event in event_reader.iter() {
+        set_focus(
+            event.target,
+            FocusCause::Programmatic,
+            &mut current_focus,
+            &mut query_focusable,
+            &mut focus_lost,
+            &mut focus_gained,
+        );
+    }
+}
+
+/// Removes focus from the focused entity when it gets disabled
+pub fn unfocus_disabled(
+    query_disabled: Query<Entity, Added<InheritedDisabled>>,
+    mut query_focusable: Query<(&mut Focusable, Option<&InheritedDisabled>)>,
+    mut current_focus: ResMut<CurrentFocus>,
+    mut focus_lost: EventWriter<FocusLost>,
+    mut focus_gained: EventWriter<FocusGained>,
+) {
+    if let Some(focus) = current_focus.0 {
+        if query_disabled.get(focus).is_ok() {
+            set_focus(
+                None,
+                FocusCause::Programmatic,
+                &mut current_focus,
+                &mut query_focusable,
+                &mut focus_lost,
+                &mut focus_gained,
+            );
+        }
+    }
+}
+
+/// Keeps `CurrentFocus` consistent with the `Focusable` entities that are spawned and despawned
+/// without going through the focus API. An entity spawned `Focused` (e.g. from a scene exported
+/// while it had the focus) is unfocused, and the focus is removed when the focused entity is
+/// despawned or stops being `Focusable`.
+pub fn reconcile_focus(
+    mut query_focusable: Query<(Entity, &mut Focusable, ChangeTrackers<Focusable>)>,
+    mut current_focus: ResMut<CurrentFocus>,
+    mut focus_lost: EventWriter<FocusLost>,
+) {
+    if let Some(focus) = current_focus.0 {
+        if query_focusable.get_mut(focus).is_err() {
+            current_focus.0 = None;
+            focus_lost.send(FocusLost {
+                entity: focus,
+                cause: FocusCause::Programmatic,
+            });
+        }
+    }
+    for (entity, mut focusable, tracker) in query_focusable.iter_mut() {
+        if tracker.is_added()
+            && matches!(*focusable, Focusable::Focused)
+            && current_focus.0 != Some(entity)
+        {
+            *focusable = Focusable::Unfocused;
         }
     }
 }
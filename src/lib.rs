@@ -1,8 +1,19 @@
+mod clipboard;
+mod disabled;
 mod focus;
+mod visual_state;
 mod widget;
 pub mod widgets;
 
-pub use focus::{CurrentFocus, FocusMaterial, Focusable};
+#[cfg(feature = "system_clipboard")]
+pub use clipboard::SystemClipboard;
+pub use clipboard::{Clipboard, ClipboardProvider, MemoryClipboard};
+pub use disabled::{Disabled, InheritedDisabled};
+pub use focus::{
+    CurrentFocus, FocusCause, FocusCommand, FocusGained, FocusLost, FocusMaterial,
+    FocusRequestEvent, FocusScope, Focusable, KeyCapture, TabIndex, WorldFocusExt,
+};
+pub use visual_state::{DisabledColor, Invalid, InvalidMaterial, VisualState};
 pub use widget::{Widget, WidgetBuilder};
 
 use bevy::{prelude::*, reflect::TypeRegistry};
@@ -13,26 +24,166 @@ pub struct WidgetsPlugin;
 
 impl Plugin for WidgetsPlugin {
     fn build(&self, app: &mut App) {
-        app.register_type::<widgets::components::ButtonMaterial>()
+        app.register_type::<widgets::components::Button>()
+            .register_type::<widgets::components::ButtonMaterial>()
+            .register_type::<widgets::components::CheckBox>()
+            .register_type::<widgets::components::Checked>()
+            .register_type::<widgets::components::RadioGroup>()
+            .register_type::<widgets::components::RadioButton>()
             .register_type::<widgets::components::InputBox>()
             .register_type::<widgets::components::Caret>()
+            .register_type::<widgets::components::CaretBlink>()
+            .register_type::<widgets::components::EditHistory>()
+            .register_type::<widgets::components::TextScroll>()
+            .register_type::<widgets::components::InputFilter>()
+            .register_type::<widgets::components::InputValidation>()
+            .register_type::<widgets::components::Masked>()
+            .register_type::<widgets::components::Autocomplete>()
+            .register_type::<widgets::components::SuggestionPopup>()
+            .register_type::<widgets::components::SuggestionRow>()
+            .register_type::<widgets::components::TextArea>()
+            .register_type::<widgets::components::SubmitShortcut>()
+            .register_type::<widgets::components::SpinBox>()
             .register_type::<Focusable>()
             .register_type::<FocusMaterial>()
+            .register_type::<TabIndex>()
+            .register_type::<FocusScope>()
+            .register_type::<KeyCapture>()
+            .register_type::<VisualState>()
+            .register_type::<Invalid>()
+            .register_type::<InvalidMaterial>()
+            .register_type::<Disabled>()
+            .register_type::<InheritedDisabled>()
+            .register_type::<DisabledColor>()
+            .add_event::<widgets::CheckBoxChanged>()
+            .add_event::<widgets::RadioSelectionChanged>()
             .add_event::<widgets::InputBoxReturnEvent>()
+            .add_event::<widgets::InputBoxInvalidEvent>()
             .add_event::<widgets::InputBoxClearEvent>()
+            .add_event::<widgets::InputBoxSetEvent>()
+            .add_event::<widgets::InputBoxChangedEvent>()
+            .add_event::<widgets::InputBoxClearHistoryEvent>()
+            .add_event::<widgets::SpinBoxChanged>()
+            .add_event::<FocusGained>()
+            .add_event::<FocusLost>()
+            .add_event::<FocusRequestEvent>()
             .insert_resource(CurrentFocus(None))
-            .add_system(widgets::components::update_button_material.system())
+            .init_resource::<Clipboard>()
+            .init_resource::<widgets::InputRules>()
+            .init_resource::<widgets::SuggestionProviders>()
+            .add_system(disabled::propagate_disabled.system())
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                disabled::reset_disabled_interaction
+                    .system()
+                    .after(bevy::ui::UiSystem::Focus),
+            )
+            .add_system(visual_state::insert_visual_state.system())
+            .add_system(
+                visual_state::resolve_visual_state
+                    .system()
+                    .label("resolve_visual_state"),
+            )
+            .add_system(
+                visual_state::update_material
+                    .system()
+                    .after("resolve_visual_state"),
+            )
+            .add_system(
+                visual_state::update_text_color
+                    .system()
+                    .after("resolve_visual_state"),
+            )
+            .add_system(widgets::components::button_keyboard.system())
+            .add_system(widgets::components::check_box_toggle.system())
+            .add_system(widgets::components::update_check_mark.system())
+            .add_system(widgets::components::radio_button_click.system())
+            .add_system(widgets::components::radio_button_keyboard.system())
+            .add_system(widgets::components::update_radio_marks.system())
             .add_system(widgets::components::show_caret.system())
             .add_system(widgets::components::move_caret.system())
-            .add_system(widgets::components::input_box_keyboard.system())
-            .add_system(widgets::components::input_box_clear.system())
-            .add_system(focus::tab_navigation.system())
-            .add_system(focus::focus_material.system())
-            .add_system(focus::mouse_focus.system());
+            .add_system(
+                widgets::components::input_box_keyboard
+                    .system()
+                    .label("input_box_keyboard"),
+            )
+            .add_system(widgets::components::input_box_mouse.system())
+            .add_system(widgets::components::update_selection.system())
+            .add_system(widgets::components::show_placeholder.system())
+            .add_system(widgets::components::update_masked_text.system())
+            .add_system(widgets::components::scroll_to_caret.system())
+            .add_system(widgets::components::text_area_wrap.system())
+            .add_system(
+                widgets::components::input_box_clear
+                    .system()
+                    .label("input_box_clear"),
+            )
+            .add_system(
+                widgets::components::input_box_set
+                    .system()
+                    .label("input_box_set")
+                    .after("input_box_clear"),
+            )
+            .add_system(
+                widgets::components::input_box_clear_history
+                    .system()
+                    .after("input_box_clear")
+                    .after("input_box_set"),
+            )
+            .add_system(
+                widgets::components::autocomplete_input
+                    .system()
+                    .after("input_box_keyboard")
+                    .after("focus_navigation"),
+            )
+            .add_system(widgets::components::update_suggestion_popup.system())
+            .add_system(widgets::components::despawn_suggestion_popup.system())
+            .add_system(widgets::components::spin_box_step.system())
+            .add_system(widgets::components::spin_box_commit.system())
+            .add_system(widgets::components::update_spin_box_text.system())
+            .add_system(focus::tab_navigation.system().label("focus_navigation"))
+            .add_system(
+                focus::directional_navigation
+                    .system()
+                    .label("focus_navigation"),
+            )
+            .add_system(focus::mouse_focus.system())
+            .add_system(focus::focus_request.system())
+            .add_system(focus::unfocus_disabled.system());
+        app.add_system_to_stage(
+            CoreStage::PostUpdate,
+            widgets::components::raise_suggestion_popup
+                .system()
+                .after(bevy::transform::TransformSystem::TransformPropagate),
+        );
+        app.add_system_to_stage(
+            CoreStage::Last,
+            focus::reconcile_focus.system().label("reconcile_focus"),
+        );
+        #[cfg(debug_assertions)]
+        app.add_system_to_stage(
+            CoreStage::Last,
+            focus::check_focus.system().after("reconcile_focus"),
+        );
+        // The plugins providing `Assets<ColorMaterial>` may be added after this one
+        app.add_startup_system(add_materials.system());
     }
 }
 
+/// Registers the materials of the weak handles used by the widgets
+fn add_materials(mut materials: ResMut<Assets<ColorMaterial>>) {
+    materials.set_untracked(
+        widgets::SELECTION_MATERIAL_HANDLE,
+        ColorMaterial::color(Color::rgba(0.2, 0.4, 1.0, 0.4)),
+    );
+    materials.set_untracked(
+        widgets::INVALID_MATERIAL_HANDLE,
+        ColorMaterial::color(Color::rgb(1.0, 0.8, 0.8)),
+    );
+}
+
 pub fn print_all(world: &mut World) {
+    let revealed = widgets::components::conceal_masked_values(world);
     let type_registry = world.get_resource::<TypeRegistry>().unwrap();
 
     println!("*****************************************");
@@ -55,6 +206,7 @@ pub fn print_all(world: &mut World) {
             }
         }
     }
+    widgets::components::restore_masked_values(world, revealed);
     // }
 }
 
@@ -156,10 +308,12 @@ fn serialize_reflect(reflect: &dyn Reflect) -> String {
 }
 
 pub fn export_scene(world: &mut World) {
+    let revealed = widgets::components::conceal_masked_values(world);
     let type_registry = world.get_resource::<TypeRegistry>().unwrap();
     let scene = DynamicScene::from_world(world, type_registry);
     let mut file = File::create("assets/test.scn.ron").unwrap();
     write!(file, "{}", scene.serialize_ron(type_registry).unwrap()).unwrap();
+    widgets::components::restore_masked_values(world, revealed);
 }
 
 pub fn import_scene_dynamic(
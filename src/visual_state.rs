@@ -0,0 +1,244 @@
+use crate::{widgets::components::ButtonMaterial, FocusMaterial, Focusable, InheritedDisabled};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Visual state of a widget, resolved from its `Interaction`, `Focusable`, `Invalid` and
+/// `InheritedDisabled` components.
+/// When several states apply, the one with the highest priority is used:
+/// `Disabled` > `Invalid` > `Clicked` > `Hovered` > `Focused` > `Normal`.
+/// A state is only used if the widget has a material for it (`ButtonMaterial` for `Clicked` and
+/// `Hovered`, `FocusMaterial` for `Focused`, `InvalidMaterial` for `Invalid`), so that a focused
+/// `InputBox` stays focused when hovered.
+#[derive(Reflect, Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize, Component)]
+#[reflect_value(Component, PartialEq, Serialize, Deserialize)]
+pub enum VisualState {
+    Normal,
+    Focused,
+    Hovered,
+    Clicked,
+    Invalid,
+    Disabled,
+}
+
+impl Default for VisualState {
+    fn default() -> Self {
+        Self::Normal
+    }
+}
+
+pub fn insert_visual_state(
+    mut cmd: Commands,
+    query: Query<
+        Entity,
+        (
+            Or<(
+                With<ButtonMaterial>,
+                With<FocusMaterial>,
+                With<InvalidMaterial>,
+                With<DisabledColor>,
+            )>,
+            Without<VisualState>,
+        ),
+    >,
+) {
+    for entity in query.iter() {
+        cmd.entity(entity).insert(VisualState::default());
+    }
+}
+
+pub fn resolve_visual_state(
+    mut query: Query<(
+        &mut VisualState,
+        Option<&Interaction>,
+        Option<&Focusable>,
+        Option<&ButtonMaterial>,
+        Option<&FocusMaterial>,
+        Option<&InvalidMaterial>,
+        Option<&Invalid>,
+        Option<&InheritedDisabled>,
+    )>,
+) {
+    for (
+        mut visual_state,
+        interaction,
+        focusable,
+        button_material,
+        focus_material,
+        invalid_material,
+        invalid,
+        disabled,
+    ) in query.iter_mut()
+    {
+        let resolved = match (interaction, focusable) {
+            _ if disabled.is_some() => VisualState::Disabled,
+            _ if invalid.is_some() && invalid_material.is_some() => VisualState::Invalid,
+            (Some(Interaction::Clicked), _) if button_material.is_some() => VisualState::Clicked,
+            (Some(Interaction::Hovered), _) if button_material.is_some() => VisualState::Hovered,
+            (_, Some(Focusable::Focused)) if focus_material.is_some() => VisualState::Focused,
+            _ => VisualState::Normal,
+        };
+        // Only trigger change detection on actual changes
+        if *visual_state != resolved {
+            *visual_state = resolved;
+        }
+    }
+}
+
+pub fn update_material(
+    mut query: Query<
+        (
+            &VisualState,
+            &mut Handle<ColorMaterial>,
+            Option<&ButtonMaterial>,
+            Option<&mut FocusMaterial>,
+            Option<&mut InvalidMaterial>,
+        ),
+        Or<(
+            Changed<VisualState>,
+            Changed<ButtonMaterial>,
+            Changed<FocusMaterial>,
+            Changed<InvalidMaterial>,
+        )>,
+    >,
+) {
+    for (visual_state, mut material, button_material, focus_material, invalid_material) in
+        query.iter_mut()
+    {
+        // Without `ButtonMaterial`, the normal material is cached in the `FocusMaterial` or the
+        // `InvalidMaterial` of the first state that replaced it
+        let cached = focus_material
+            .as_ref()
+            .map_or(false, |focus_material| focus_material.cache.is_some())
+            || invalid_material
+                .as_ref()
+                .map_or(false, |invalid_material| invalid_material.cache.is_some());
+        let resolved = match (
+            visual_state,
+            button_material,
+            focus_material,
+            invalid_material,
+        ) {
+            (VisualState::Disabled, Some(button_material), _, _) => {
+                button_material.material_disabled.clone()
+            }
+            (VisualState::Invalid, None, _, Some(mut invalid_material)) => {
+                if !cached {
+                    invalid_material.cache = Some(material.clone());
+                }
+                invalid_material.material.clone()
+            }
+            (VisualState::Invalid, Some(_), _, Some(invalid_material)) => {
+                invalid_material.material.clone()
+            }
+            (VisualState::Clicked, Some(button_material), _, _) => {
+                button_material.material_clicked.clone()
+            }
+            (VisualState::Hovered, Some(button_material), _, _) => {
+                button_material.material_hovered.clone()
+            }
+            (VisualState::Focused, None, Some(mut focus_material), _) => {
+                if !cached {
+                    focus_material.cache = Some(material.clone());
+                }
+                focus_material.material.clone()
+            }
+            (VisualState::Focused, Some(_), Some(focus_material), _) => {
+                focus_material.material.clone()
+            }
+            (_, Some(button_material), _, _) => button_material.material.clone(),
+            (_, None, focus_material, invalid_material) => {
+                let cache = match (focus_material, invalid_material) {
+                    (Some(mut focus_material), _) if focus_material.cache.is_some() => {
+                        focus_material.cache.take()
+                    }
+                    (_, Some(mut invalid_material)) if invalid_material.cache.is_some() => {
+                        invalid_material.cache.take()
+                    }
+                    _ => None,
+                };
+                if let Some(cached) = cache {
+                    cached
+                } else {
+                    continue;
+                }
+            }
+        };
+        if *material != resolved {
+            *material = resolved;
+        }
+    }
+}
+
+/// Marks a widget whose content failed its validation, see `VisualState`
+#[derive(Reflect, Default, Component)]
+#[reflect(Component)]
+pub struct Invalid;
+
+/// Material used while the entity is `Invalid`, see `VisualState`
+#[derive(Reflect, Default, Component)]
+#[reflect(Component)]
+pub struct InvalidMaterial {
+    pub material: Handle<ColorMaterial>,
+    // Material to restore when the entity goes back to `VisualState::Normal`,
+    // only used when the entity has no `ButtonMaterial`
+    #[reflect(ignore)]
+    cache: Option<Handle<ColorMaterial>>,
+}
+
+impl InvalidMaterial {
+    pub fn new(material: Handle<ColorMaterial>) -> Self {
+        Self {
+            material,
+            cache: None,
+        }
+    }
+}
+
+/// Text color used while the entity is disabled, applied to all the sections of its `Text`
+#[derive(Reflect, Default, Component)]
+#[reflect(Component)]
+pub struct DisabledColor {
+    pub color: Color,
+    // Colors to restore when the entity is enabled again
+    #[reflect(ignore)]
+    cache: Vec<Color>,
+}
+
+impl DisabledColor {
+    pub fn new(color: Color) -> Self {
+        Self {
+            color,
+            cache: Vec::new(),
+        }
+    }
+}
+
+pub fn update_text_color(
+    mut query: Query<
+        (&VisualState, &mut DisabledColor, &mut Text),
+        Or<(Changed<VisualState>, Changed<DisabledColor>)>,
+    >,
+) {
+    for (visual_state, mut disabled_color, mut text) in query.iter_mut() {
+        match visual_state {
+            VisualState::Disabled => {
+                if disabled_color.cache.is_empty() {
+                    disabled_color.cache = text
+                        .sections
+                        .iter()
+                        .map(|section| section.style.color)
+                        .collect();
+                }
+                for section in text.sections.iter_mut() {
+                    section.style.color = disabled_color.color;
+                }
+            }
+            _ => {
+                let cache = std::mem::take(&mut disabled_color.cache);
+                for (section, color) in text.sections.iter_mut().zip(cache) {
+                    section.style.color = color;
+                }
+            }
+        }
+    }
+}
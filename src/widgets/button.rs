@@ -1,5 +1,5 @@
 use super::Base;
-use crate::{Focusable, Widget, WidgetBuilder};
+use crate::{Focusable, VisualState, Widget, WidgetBuilder};
 use bevy::{
     ecs::world::EntityMut,
     prelude::{ButtonBundle, Children, Entity},
@@ -17,27 +17,63 @@ pub mod components {
         sprite::ColorMaterial,
     };
 
+    use crate::{CurrentFocus, InheritedDisabled};
+
+    /// Marker component for buttons, which can be activated with the keyboard or a gamepad when focused
+    #[derive(Default, Reflect, Component)]
+    #[reflect(Component)]
+    pub struct Button;
+
+    const ACTIVATION_KEYS: [KeyCode; 3] = [KeyCode::Return, KeyCode::NumpadEnter, KeyCode::Space];
+
+    /// Sets `Interaction::Clicked` on the focused `Button` while an activation key is held,
+    /// so that keyboard activation has the same effects as a mouse click
+    pub fn button_keyboard(
+        mut pressed: Local<Option<Entity>>,
+        keyboard_input: Res<Input<KeyCode>>,
+        gamepad_input: Res<Input<GamepadButton>>,
+        current_focus: Res<CurrentFocus>,
+        mut query: Query<&mut Interaction, (With<Button>, Without<InheritedDisabled>)>,
+    ) {
+        let just_released = ACTIVATION_KEYS
+            .iter()
+            .any(|key| keyboard_input.just_released(*key))
+            || gamepad_input
+                .get_just_released()
+                .any(|button| button.1 == GamepadButtonType::South);
+        if just_released {
+            if let Some(entity) = pressed.take() {
+                if let Ok(mut interaction) = query.get_mut(entity) {
+                    if *interaction == Interaction::Clicked {
+                        *interaction = Interaction::None;
+                    }
+                }
+            }
+        }
+        let just_pressed = ACTIVATION_KEYS
+            .iter()
+            .any(|key| keyboard_input.just_pressed(*key))
+            || gamepad_input
+                .get_just_pressed()
+                .any(|button| button.1 == GamepadButtonType::South);
+        if just_pressed && pressed.is_none() {
+            if let Some(entity) = current_focus.0 {
+                if let Ok(mut interaction) = query.get_mut(entity) {
+                    *interaction = Interaction::Clicked;
+                    *pressed = Some(entity);
+                }
+            }
+        }
+    }
+
+    /// Materials used depending on the `VisualState`
     #[derive(Default, Reflect, Component)]
     #[reflect(Component)]
     pub struct ButtonMaterial {
         pub material: Handle<ColorMaterial>,
         pub material_hovered: Handle<ColorMaterial>,
         pub material_clicked: Handle<ColorMaterial>,
-    }
-
-    pub fn update_button_material(
-        mut query: Query<
-            (&ButtonMaterial, &mut Handle<ColorMaterial>, &Interaction),
-            Or<(Changed<ButtonMaterial>, Changed<Interaction>)>,
-        >,
-    ) {
-        for (button, mut material, interaction) in query.iter_mut() {
-            *material = match interaction {
-                Interaction::Clicked => button.material_clicked.clone(),
-                Interaction::Hovered => button.material_hovered.clone(),
-                Interaction::None => button.material.clone(),
-            };
-        }
+        pub material_disabled: Handle<ColorMaterial>,
     }
 
     #[derive(Reflect, Component, Default)]
@@ -64,7 +100,10 @@ pub mod components {
     }
 
     pub fn event_button<T: Reflect + Default + MapEntities + Clone + Send + Sync + 'static>(
-        query: Query<(&EventButton<T>, &Interaction), Changed<Interaction>>,
+        query: Query<
+            (&EventButton<T>, &Interaction),
+            (Changed<Interaction>, Without<InheritedDisabled>),
+        >,
         mut event_writer: EventWriter<T>,
     ) {
         for (event_button, interaction) in query.iter() {
@@ -101,7 +140,9 @@ impl Button {
                 },
                 ..Default::default()
             })
+            .insert(components::Button)
             .insert(components::ButtonMaterial::default())
+            .insert(VisualState::default())
             .insert(Focusable::default())
             .push_children(&[child]);
         Self { base }.with_child(|mut child| {
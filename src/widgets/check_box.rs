@@ -0,0 +1,187 @@
+use super::{Base, Label};
+use crate::{Focusable, VisualState, Widget, WidgetBuilder};
+use bevy::{ecs::world::EntityMut, prelude::*, ui::FocusPolicy};
+
+pub struct CheckBox {
+    base: Base,
+    mark: Entity,
+}
+
+/// Sent when a `CheckBox` is toggled by the user
+pub struct CheckBoxChanged {
+    pub source: Entity,
+    pub checked: bool,
+}
+
+pub mod components {
+    use bevy::{
+        ecs::{
+            entity::{EntityMap, MapEntities, MapEntitiesError},
+            reflect::ReflectMapEntities,
+        },
+        prelude::*,
+    };
+
+    use super::CheckBoxChanged;
+
+    #[derive(Reflect, Component)]
+    #[reflect(Component, MapEntities)]
+    pub struct CheckBox {
+        pub mark: Entity,
+    }
+
+    // I hate this
+    impl FromWorld for CheckBox {
+        fn from_world(_world: &mut bevy::prelude::World) -> Self {
+            Self {
+                mark: Entity::new(u32::MAX),
+            }
+        }
+    }
+
+    // This could be derived eventually
+    impl MapEntities for CheckBox {
+        fn map_entities(&mut self, entity_map: &EntityMap) -> Result<(), MapEntitiesError> {
+            self.mark = entity_map.get(self.mark)?;
+            Ok(())
+        }
+    }
+
+    #[derive(Reflect, Default, Clone, Copy, Component)]
+    #[reflect(Component)]
+    pub struct Checked(pub bool);
+
+    /// Toggles the `CheckBox` when clicked, or activated with the keyboard (see `button_keyboard`)
+    pub fn check_box_toggle(
+        mut query: Query<
+            (Entity, &mut Checked, &Interaction),
+            (Changed<Interaction>, With<CheckBox>),
+        >,
+        mut event_writer: EventWriter<CheckBoxChanged>,
+    ) {
+        for (entity, mut checked, interaction) in query.iter_mut() {
+            if matches!(interaction, Interaction::Clicked) {
+                checked.0 = !checked.0;
+                event_writer.send(CheckBoxChanged {
+                    source: entity,
+                    checked: checked.0,
+                });
+            }
+        }
+    }
+
+    pub fn update_check_mark(
+        query_box: Query<(&CheckBox, &Checked), Changed<Checked>>,
+        mut query_mark: Query<&mut Visible>,
+    ) {
+        for (check_box, checked) in query_box.iter() {
+            if let Ok(mut visible) = query_mark.get_mut(check_box.mark) {
+                visible.is_visible = checked.0;
+            }
+        }
+    }
+}
+
+impl CheckBox {
+    pub fn new(wb: &WidgetBuilder) -> Self {
+        let (square, mark) = spawn_indicator(wb, "X");
+        let base = spawn_toggle(wb, square).insert(components::CheckBox { mark });
+        Self { base, mark }.checked(false)
+    }
+
+    /// Adds a label after the box, clicking it also toggles the `CheckBox`
+    pub fn label(self, label: &str) -> Self {
+        let label = Label::new(self.builder(), label)
+            .insert(FocusPolicy::Pass)
+            .root_id();
+        self.push_children(&[label])
+    }
+
+    pub fn checked(self, checked: bool) -> Self {
+        self.insert(components::Checked(checked)).mark(|mut mark| {
+            mark.get_mut::<Visible>().unwrap().is_visible = checked;
+        })
+    }
+
+    pub fn mark(self, f: impl FnOnce(EntityMut)) -> Self {
+        f(self.builder().world_mut().entity_mut(self.mark));
+        self
+    }
+}
+
+/// Spawns a square with a centered symbol, returns the square and the symbol entities.
+/// Shared by `CheckBox` and `RadioButton`.
+pub(super) fn spawn_indicator(wb: &WidgetBuilder, symbol: &str) -> (Entity, Entity) {
+    let mark = wb
+        .world_mut()
+        .spawn()
+        .insert_bundle(TextBundle {
+            style: Style {
+                size: Size {
+                    width: Val::Undefined,
+                    height: Val::Px(16.),
+                },
+                margin: Rect::all(Val::Auto),
+                flex_shrink: 0.,
+                ..Default::default()
+            },
+            text: Text::with_section(
+                symbol,
+                TextStyle {
+                    font: wb.default_font.clone(),
+                    font_size: 16.0,
+                    color: Color::BLACK,
+                },
+                Default::default(),
+            ),
+            focus_policy: FocusPolicy::Pass,
+            ..Default::default()
+        })
+        .id();
+    let square = wb
+        .world_mut()
+        .spawn()
+        .insert_bundle(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Px(16.), Val::Px(16.)),
+                margin: Rect::all(Val::Px(2.0)),
+                align_self: AlignSelf::Center,
+                flex_shrink: 0.,
+                ..Default::default()
+            },
+            focus_policy: FocusPolicy::Pass,
+            ..Default::default()
+        })
+        .push_children(&[mark])
+        .id();
+    (square, mark)
+}
+
+/// Spawns a focusable button containing `indicator`, that can be activated with the keyboard.
+/// Shared by `CheckBox` and `RadioButton`.
+pub(super) fn spawn_toggle(wb: &WidgetBuilder, indicator: Entity) -> Base {
+    Base::spawn(wb)
+        .insert_bundle(ButtonBundle {
+            style: Style {
+                flex_direction: FlexDirection::Row,
+                flex_shrink: 0.,
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .insert(super::components::Button)
+        .insert(super::components::ButtonMaterial::default())
+        .insert(VisualState::default())
+        .insert(Focusable::default())
+        .push_children(&[indicator])
+}
+
+impl Widget for CheckBox {
+    fn builder(&self) -> &WidgetBuilder {
+        self.base.builder()
+    }
+
+    fn root_id(&self) -> Entity {
+        self.base.root_id()
+    }
+}
@@ -1,11 +1,35 @@
 use super::Base;
-use crate::{Focusable, Widget, WidgetBuilder};
-use bevy::{ecs::world::EntityMut, prelude::*, ui::FocusPolicy};
+use crate::{DisabledColor, Focusable, InvalidMaterial, KeyCapture, Widget, WidgetBuilder};
+use bevy::{ecs::world::EntityMut, prelude::*, reflect::TypeUuid, ui::FocusPolicy};
+
+mod autocomplete;
+mod history;
+mod lines;
+mod mask;
+mod segmentation;
+mod validation;
+
+pub use autocomplete::{
+    HistorySuggestions, StaticSuggestions, SuggestionProvider, SuggestionProviders,
+};
+pub use history::{EditKind, EditSnapshot, InputBoxClearHistoryEvent};
+pub use mask::MASK;
+pub use validation::{CharFilter, InputBoxInvalidEvent, InputRules, InvalidReason, TextValidator};
+
+/// Material used to highlight the selected text, registered by the `WidgetsPlugin`
+pub const SELECTION_MATERIAL_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(ColorMaterial::TYPE_UUID, 0x5e1e_c710_4b0c_0001);
+
+/// Material of an `InputBox` whose content failed its validation, registered by the `WidgetsPlugin`
+pub const INVALID_MATERIAL_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(ColorMaterial::TYPE_UUID, 0x5e1e_c710_4b0c_0002);
 
 pub struct InputBox {
     base: Base,
     text: Entity,
     caret: Entity,
+    selection: Entity,
+    placeholder: Entity,
 }
 
 pub struct InputBoxReturnEvent {
@@ -13,10 +37,26 @@ pub struct InputBoxReturnEvent {
     pub text: String,
 }
 
+/// Empties an `InputBox`. Clear events are applied before the `InputBoxSetEvent`s sent in the
+/// same frame.
 pub struct InputBoxClearEvent {
     pub target: Entity,
 }
 
+/// Replaces the content of an `InputBox`, and moves the caret to its end
+pub struct InputBoxSetEvent {
+    pub target: Entity,
+    pub text: String,
+}
+
+/// Sent on every edit of the content of an `InputBox`, by the user or by an event
+pub struct InputBoxChangedEvent {
+    pub source: Entity,
+    pub old: String,
+    pub new: String,
+    pub kind: EditKind,
+}
+
 pub mod components {
     use bevy::{
         ecs::{
@@ -25,17 +65,42 @@ pub mod components {
         },
         prelude::*,
         text::DefaultTextPipeline,
+        ui::FocusPolicy,
     };
+    use std::ops::Range;
 
-    use crate::Focusable;
+    use crate::{
+        widgets::components::TextArea, Clipboard, Focusable, InheritedDisabled, Invalid, KeyCapture,
+    };
 
-    use super::{InputBoxClearEvent, InputBoxReturnEvent};
+    pub use super::autocomplete::{
+        autocomplete_input, despawn_suggestion_popup, raise_suggestion_popup,
+        update_suggestion_popup, Autocomplete, SuggestionPopup, SuggestionRow,
+    };
+    pub use super::history::{input_box_clear_history, EditHistory};
+    pub(crate) use super::mask::{conceal_masked_values, restore_masked_values};
+    pub use super::mask::{update_masked_text, Masked};
+    pub use super::validation::{InputFilter, InputValidation};
+    use super::{
+        history::{EditKind, EditSnapshot},
+        lines::{TextLines, LINE_SPACING},
+        mask::DisplayMap,
+        segmentation::{
+            next_grapheme_boundary, next_word_boundary, previous_grapheme_boundary,
+            previous_word_boundary,
+        },
+        validation::{length_without, InputBoxInvalidEvent, InputRules},
+        InputBoxChangedEvent, InputBoxClearEvent, InputBoxReturnEvent, InputBoxSetEvent,
+        SELECTION_MATERIAL_HANDLE,
+    };
 
     #[derive(Reflect, Component)]
     #[reflect(Component, MapEntities)]
     pub struct InputBox {
         pub text: Entity,
         pub caret: Entity,
+        pub selection: Entity,
+        pub placeholder: Entity,
     }
 
     // I hate this
@@ -44,6 +109,8 @@ pub mod components {
             Self {
                 text: Entity::new(u32::MAX),
                 caret: Entity::new(u32::MAX),
+                selection: Entity::new(u32::MAX),
+                placeholder: Entity::new(u32::MAX),
             }
         }
     }
@@ -53,15 +120,58 @@ pub mod components {
         fn map_entities(&mut self, entity_map: &EntityMap) -> Result<(), MapEntitiesError> {
             self.text = entity_map.get(self.text)?;
             self.caret = entity_map.get(self.caret)?;
+            self.selection = entity_map.get(self.selection)?;
+            self.placeholder = entity_map.get(self.placeholder)?;
             Ok(())
         }
     }
 
+    /// The caret is the extent of the selection, the anchor is where it started.
+    /// Both are byte indices in the text, nothing is selected when they are equal.
+    /// They are always on a grapheme cluster boundary, so that characters made of several
+    /// code points (accents, emoji modifiers, flags) are moved over and deleted as a whole.
     #[derive(Reflect, Component)]
     #[reflect(Component, MapEntities)]
     pub struct Caret {
         pub text: Entity,
         pub character_index: usize,
+        pub anchor: usize,
+    }
+
+    impl Caret {
+        /// The selected byte range, if it is not empty
+        pub fn selection(&self) -> Option<Range<usize>> {
+            if self.anchor == self.character_index {
+                None
+            } else {
+                let start = self.anchor.min(self.character_index);
+                let end = self.anchor.max(self.character_index);
+                Some(start..end)
+            }
+        }
+
+        pub fn collapse(&mut self) {
+            self.anchor = self.character_index;
+        }
+
+        /// Whether both indices are character boundaries of `string`
+        pub(crate) fn is_inside(&self, string: &str) -> bool {
+            string.is_char_boundary(self.character_index) && string.is_char_boundary(self.anchor)
+        }
+
+        /// Moves both indices back inside `string`. The value of a `Masked` box is not saved in
+        /// scenes, so it is reloaded empty while its `Caret` keeps its indices.
+        pub(crate) fn clamp_to(&mut self, string: &str) {
+            let clamp = |index: usize| {
+                let mut index = index.min(string.len());
+                while !string.is_char_boundary(index) {
+                    index -= 1;
+                }
+                index
+            };
+            self.character_index = clamp(self.character_index);
+            self.anchor = clamp(self.anchor);
+        }
     }
 
     // I hate this
@@ -70,6 +180,7 @@ pub mod components {
             Self {
                 text: Entity::new(u32::MAX),
                 character_index: 0,
+                anchor: 0,
             }
         }
     }
@@ -82,43 +193,108 @@ pub mod components {
         }
     }
 
+    /// Makes the `Caret` blink while its `InputBox` is focused.
+    /// The blinking restarts on every keystroke, so that the caret stays visible while typing.
+    #[derive(Reflect, Component)]
+    #[reflect(Component)]
+    pub struct CaretBlink {
+        /// Duration of a full blink (visible then hidden), in seconds
+        pub period: f32,
+        /// The caret stays visible when disabled, for users sensitive to blinking
+        pub enabled: bool,
+        #[reflect(ignore)]
+        elapsed: f32,
+    }
+
+    impl Default for CaretBlink {
+        fn default() -> Self {
+            Self::new(1.06)
+        }
+    }
+
+    impl CaretBlink {
+        pub fn new(period: f32) -> Self {
+            Self {
+                period,
+                enabled: true,
+                elapsed: 0.,
+            }
+        }
+
+        pub fn disabled() -> Self {
+            Self {
+                enabled: false,
+                ..Default::default()
+            }
+        }
+    }
+
     pub fn show_caret(
-        mut query_caret: Query<&mut Visible, With<Caret>>,
-        query_box: Query<(&InputBox, &Focusable), Changed<Focusable>>,
+        time: Res<Time>,
+        keyboard_input: Res<Input<KeyCode>>,
+        mut query_caret: Query<
+            (&mut Visible, Option<&mut CaretBlink>, ChangeTrackers<Caret>),
+            With<Caret>,
+        >,
+        query_box: Query<(&InputBox, &Focusable, ChangeTrackers<Focusable>)>,
     ) {
-        for (input_box, focusable) in query_box.iter() {
-            let mut caret_visible = query_caret.get_mut(input_box.caret).unwrap();
-            match focusable {
-                Focusable::Focused => caret_visible.is_visible = true,
-                Focusable::Unfocused => caret_visible.is_visible = false,
+        let keystroke = keyboard_input.get_just_pressed().next().is_some();
+        for (input_box, focusable, focus_tracker) in query_box.iter() {
+            let (mut caret_visible, blink, caret_tracker) =
+                query_caret.get_mut(input_box.caret).unwrap();
+            let focused = matches!(focusable, Focusable::Focused);
+            let is_visible = match blink {
+                Some(mut blink) if focused && blink.enabled && blink.period > 0. => {
+                    if keystroke || focus_tracker.is_changed() || caret_tracker.is_changed() {
+                        blink.elapsed = 0.;
+                    } else {
+                        blink.elapsed = (blink.elapsed + time.delta_seconds()) % blink.period;
+                    }
+                    blink.elapsed < blink.period / 2.
+                }
+                _ => focused,
+            };
+            if caret_visible.is_visible != is_visible {
+                caret_visible.is_visible = is_visible;
             }
         }
     }
 
+    /// Positions the caret on its line, over the masks if the value is hidden.
+    /// The text is laid out after the caret moves, so this is checked every frame.
     pub fn move_caret(
-        mut query_caret: Query<(&Caret, &mut Style), Changed<Caret>>,
+        query_box: Query<(&InputBox, Option<&Masked>)>,
+        mut query_caret: Query<(&Caret, &mut Style)>,
         query_text: Query<&Text>,
         text_pipeline: Res<DefaultTextPipeline>,
     ) {
-        for (caret, mut style) in query_caret.iter_mut() {
-            if caret.character_index == 0 {
-                style.position.left = Val::Px(0.);
-                style.position.bottom = Val::Px(0.);
-            } else if let Some(layout_info) = text_pipeline.get_glyphs(&caret.text) {
-                let text = query_text.get(caret.text).unwrap();
-                if caret.character_index == text.sections[0].value.len() {
-                    style.position.left = Val::Px(layout_info.size.width);
-                    style.position.bottom = Val::Px(0.);
-                } else {
-                    for glyph in &layout_info.glyphs {
-                        if glyph.byte_index == caret.character_index {
-                            style.position.left =
-                                Val::Px(glyph.position.x - glyph.size.x / 2. - 2.);
-                            style.position.bottom = Val::Px(0.);
-                            break;
-                        }
-                    }
+        for (input_box, masked) in query_box.iter() {
+            let (caret, mut style) = query_caret.get_mut(input_box.caret).unwrap();
+            let text = query_text.get(input_box.text).unwrap();
+            let index = DisplayMap::new(masked).to_display(caret.character_index);
+            let (left, line) = match text_pipeline.get_glyphs(&input_box.text) {
+                Some(layout_info) => {
+                    let lines = TextLines::new(layout_info, &text.sections[0].value);
+                    let line = lines.line_of(index);
+                    let range = lines.line_range(line);
+                    let offset = lines.offset(index);
+                    // The caret glyph has some bearing, except at the start and the end of a line
+                    let left = if index == range.start {
+                        0.
+                    } else if index == range.end {
+                        offset
+                    } else {
+                        offset - 2.
+                    };
+                    (left, line)
                 }
+                None => (0., 0),
+            };
+            let left = Val::Px(left);
+            let bottom = Val::Px(line as f32 * text.sections[0].style.font_size * LINE_SPACING);
+            if style.position.left != left || style.position.bottom != bottom {
+                style.position.left = left;
+                style.position.bottom = bottom;
             }
         }
     }
@@ -126,96 +302,841 @@ pub mod components {
     const BACKSPACE: char = '\u{8}';
     const DELETE: char = '\u{7f}';
     const RETURN: char = '\r';
+    const NEWLINE: char = '\n';
 
+    /// Edits the focused `InputBox`es. Typed and pasted characters go through their `InputFilter`,
+    /// and their content is checked against their `InputValidation` when it is submitted.
+    /// A hidden `Masked` value can't be copied, and is moved over as a single word.
+    #[allow(clippy::too_many_arguments)]
     pub fn input_box_keyboard(
-        query_box: Query<(Entity, &InputBox, &Focusable)>,
+        mut cmd: Commands,
+        mut query_box: Query<
+            (
+                Entity,
+                &InputBox,
+                &Focusable,
+                &mut EditHistory,
+                Option<&TextArea>,
+                Option<&InputFilter>,
+                Option<&InputValidation>,
+                Option<&Invalid>,
+                Option<&mut Masked>,
+                Option<&KeyCapture>,
+            ),
+            Without<InheritedDisabled>,
+        >,
         mut query_text: Query<&mut Text>,
         mut query_caret: Query<&mut Caret>,
         mut character_events: EventReader<ReceivedCharacter>,
         keyboard_input: Res<Input<KeyCode>>,
+        mut clipboard: ResMut<Clipboard>,
+        rules: Res<InputRules>,
         mut event_writer: EventWriter<InputBoxReturnEvent>,
+        mut invalid_event_writer: EventWriter<InputBoxInvalidEvent>,
+        mut changed_event_writer: EventWriter<InputBoxChangedEvent>,
+        text_pipeline: Res<DefaultTextPipeline>,
     ) {
+        let shift =
+            keyboard_input.pressed(KeyCode::LShift) || keyboard_input.pressed(KeyCode::RShift);
+        let ctrl =
+            keyboard_input.pressed(KeyCode::LControl) || keyboard_input.pressed(KeyCode::RControl);
         // TODO: add marker compoennt to reduce query conflicts?
-        for (id, input_box, focusable) in query_box.iter() {
+        for (
+            id,
+            input_box,
+            focusable,
+            mut history,
+            text_area,
+            filter,
+            validation,
+            invalid,
+            mut masked,
+            key_capture,
+        ) in query_box.iter_mut()
+        {
             if matches!(focusable, Focusable::Focused) {
+                let hidden = masked.as_ref().map_or(false, |masked| !masked.reveal);
+                let capture = key_capture.copied().unwrap_or_default();
                 let mut text = query_text.get_mut(input_box.text).unwrap();
-                let string = &mut text.sections[0].value;
+                // The `Text` of a `Masked` box is updated from its value
+                let string = match masked.as_mut() {
+                    Some(masked) => &mut masked.value,
+                    None => &mut text.sections[0].value,
+                };
+                let previous_word = |string: &str, index: usize| {
+                    if hidden {
+                        0
+                    } else {
+                        previous_word_boundary(string, index)
+                    }
+                };
+                let next_word = |string: &str, index: usize| {
+                    if hidden {
+                        string.len()
+                    } else {
+                        next_word_boundary(string, index)
+                    }
+                };
                 let mut caret = query_caret.get_mut(input_box.caret).unwrap();
-                if keyboard_input.just_pressed(KeyCode::Left) {
-                    move_left(string, &mut caret.character_index);
+                if !caret.is_inside(string) {
+                    caret.clamp_to(string);
+                }
+                // The error state is cleared once the content is edited
+                let invalid_text = invalid.map(|_| string.clone());
+                let multiline = text_area.is_some();
+                let accepts = |length: usize, c: char| {
+                    filter.map_or(true, |filter| filter.accepts(&rules, length, c))
+                };
+                // Lines are only needed to move the caret in a `TextArea`
+                let layout_info = text_pipeline
+                    .get_glyphs(&input_box.text)
+                    .filter(|_| text_area.is_some() && !hidden);
+                if ctrl && keyboard_input.just_pressed(KeyCode::Z) && !shift {
+                    let old = string.clone();
+                    if history.undo(string, &mut caret) {
+                        changed_event_writer.send(InputBoxChangedEvent {
+                            source: id,
+                            old,
+                            new: string.clone(),
+                            kind: EditKind::Undo,
+                        });
+                    }
+                } else if ctrl
+                    && (keyboard_input.just_pressed(KeyCode::Y)
+                        || keyboard_input.just_pressed(KeyCode::Z))
+                {
+                    let old = string.clone();
+                    if history.redo(string, &mut caret) {
+                        changed_event_writer.send(InputBoxChangedEvent {
+                            source: id,
+                            old,
+                            new: string.clone(),
+                            kind: EditKind::Redo,
+                        });
+                    }
+                } else if ctrl && keyboard_input.just_pressed(KeyCode::A) {
+                    caret.anchor = 0;
+                    caret.character_index = string.len();
+                } else if ctrl && keyboard_input.just_pressed(KeyCode::C) {
+                    if let Some(selection) = caret.selection().filter(|_| !hidden) {
+                        clipboard.set_contents(string[selection].to_string());
+                    }
+                } else if ctrl && keyboard_input.just_pressed(KeyCode::X) {
+                    if let Some(selection) = caret.selection().filter(|_| !hidden) {
+                        clipboard.set_contents(string[selection].to_string());
+                        let before = EditSnapshot::new(string, &caret);
+                        remove_selection(string, &mut caret);
+                        record_edit(
+                            id,
+                            EditKind::Cut,
+                            before,
+                            string,
+                            &caret,
+                            &mut history,
+                            &mut changed_event_writer,
+                        );
+                    }
+                } else if ctrl && keyboard_input.just_pressed(KeyCode::V) {
+                    if let Some(contents) = clipboard.get_contents() {
+                        let before = EditSnapshot::new(string, &caret);
+                        remove_selection(string, &mut caret);
+                        insert_str(
+                            string,
+                            &mut caret.character_index,
+                            &contents,
+                            |string, c| {
+                                (!c.is_control() || (multiline && c == NEWLINE))
+                                    && accepts(string.chars().count(), c)
+                            },
+                        );
+                        caret.collapse();
+                        record_edit(
+                            id,
+                            EditKind::Paste,
+                            before,
+                            string,
+                            &caret,
+                            &mut history,
+                            &mut changed_event_writer,
+                        );
+                    }
+                } else if keyboard_input.just_pressed(KeyCode::Left) {
+                    match caret.selection() {
+                        Some(selection) if !shift => caret.character_index = selection.start,
+                        _ if ctrl => {
+                            caret.character_index = previous_word(string, caret.character_index)
+                        }
+                        _ => move_left(string, &mut caret.character_index),
+                    }
+                    if !shift {
+                        caret.collapse();
+                    }
                 } else if keyboard_input.just_pressed(KeyCode::Right) {
-                    move_right(string, &mut caret.character_index);
+                    match caret.selection() {
+                        Some(selection) if !shift => caret.character_index = selection.end,
+                        _ if ctrl => {
+                            caret.character_index = next_word(string, caret.character_index)
+                        }
+                        _ => move_right(string, &mut caret.character_index),
+                    }
+                    if !shift {
+                        caret.collapse();
+                    }
+                } else if !capture.arrows
+                    && (keyboard_input.just_pressed(KeyCode::Up)
+                        || keyboard_input.just_pressed(KeyCode::Down))
+                {
+                    if let Some(layout_info) = layout_info {
+                        let lines = TextLines::new(layout_info, string);
+                        let line = lines.line_of(caret.character_index);
+                        let x = lines.offset(caret.character_index);
+                        caret.character_index = if keyboard_input.just_pressed(KeyCode::Up) {
+                            match line {
+                                0 => 0,
+                                line => lines.index_at(line - 1, x),
+                            }
+                        } else if line + 1 == lines.line_count() {
+                            string.len()
+                        } else {
+                            lines.index_at(line + 1, x)
+                        };
+                        if !shift {
+                            caret.collapse();
+                        }
+                    }
+                } else if keyboard_input.just_pressed(KeyCode::Home) {
+                    caret.character_index = match layout_info {
+                        Some(layout_info) if !ctrl => {
+                            let lines = TextLines::new(layout_info, string);
+                            lines.line_range(lines.line_of(caret.character_index)).start
+                        }
+                        _ => 0,
+                    };
+                    if !shift {
+                        caret.collapse();
+                    }
+                } else if keyboard_input.just_pressed(KeyCode::End) {
+                    caret.character_index = match layout_info {
+                        Some(layout_info) if !ctrl => {
+                            let lines = TextLines::new(layout_info, string);
+                            lines.line_range(lines.line_of(caret.character_index)).end
+                        }
+                        _ => string.len(),
+                    };
+                    if !shift {
+                        caret.collapse();
+                    }
                 }
                 for character in character_events.iter() {
                     // TODO: handle WindowId?
                     // TODO: handle multiple text sections
+                    let before = EditSnapshot::new(string, &caret);
+                    // The selection is replaced, so it does not count in the length
+                    let length = length_without(string, caret.selection());
                     match character.char {
                         c if !c.is_control() => {
+                            if !accepts(length, c) {
+                                continue;
+                            }
+                            let kind = if remove_selection(string, &mut caret) {
+                                EditKind::Replace
+                            } else {
+                                EditKind::Insert
+                            };
                             insert_char(string, &mut caret.character_index, c);
+                            caret.collapse();
+                            record_edit(
+                                id,
+                                kind,
+                                before,
+                                string,
+                                &caret,
+                                &mut history,
+                                &mut changed_event_writer,
+                            );
                         }
                         BACKSPACE => {
-                            move_left(string, &mut caret.character_index);
-                            remove_char(string, &mut caret.character_index);
+                            if !remove_selection(string, &mut caret) {
+                                if ctrl {
+                                    let start = previous_word(string, caret.character_index);
+                                    string.replace_range(start..caret.character_index, "");
+                                    caret.character_index = start;
+                                } else {
+                                    move_left(string, &mut caret.character_index);
+                                    remove_char(string, &mut caret.character_index);
+                                }
+                                caret.collapse();
+                            }
+                            record_edit(
+                                id,
+                                EditKind::Delete,
+                                before,
+                                string,
+                                &caret,
+                                &mut history,
+                                &mut changed_event_writer,
+                            );
                         }
-                        DELETE => remove_char(string, &mut caret.character_index),
-                        RETURN => event_writer.send(InputBoxReturnEvent {
-                            source: id,
-                            text: string.clone(),
-                        }),
+                        DELETE => {
+                            if !remove_selection(string, &mut caret) {
+                                if ctrl {
+                                    let end = next_word(string, caret.character_index);
+                                    string.replace_range(caret.character_index..end, "");
+                                } else {
+                                    remove_char(string, &mut caret.character_index);
+                                }
+                            }
+                            record_edit(
+                                id,
+                                EditKind::Delete,
+                                before,
+                                string,
+                                &caret,
+                                &mut history,
+                                &mut changed_event_writer,
+                            );
+                        }
+                        // Enter is kept by the focused widget, e.g. to accept a suggestion
+                        RETURN | NEWLINE if capture.enter => {}
+                        // Ctrl+Enter sends a new line on some platforms
+                        RETURN | NEWLINE => match text_area {
+                            Some(text_area) if !text_area.submit.is_pressed(ctrl, shift) => {
+                                if !accepts(length, NEWLINE) {
+                                    continue;
+                                }
+                                let kind = if remove_selection(string, &mut caret) {
+                                    EditKind::Replace
+                                } else {
+                                    EditKind::Insert
+                                };
+                                insert_char(string, &mut caret.character_index, NEWLINE);
+                                caret.collapse();
+                                record_edit(
+                                    id,
+                                    kind,
+                                    before,
+                                    string,
+                                    &caret,
+                                    &mut history,
+                                    &mut changed_event_writer,
+                                );
+                            }
+                            _ => match validation
+                                .map_or(Ok(()), |validation| validation.validate(&rules, string))
+                            {
+                                Ok(()) => {
+                                    if invalid.is_some() {
+                                        cmd.entity(id).remove::<Invalid>();
+                                    }
+                                    event_writer.send(InputBoxReturnEvent {
+                                        source: id,
+                                        text: string.clone(),
+                                    })
+                                }
+                                Err(reason) => {
+                                    cmd.entity(id).insert(Invalid);
+                                    invalid_event_writer.send(InputBoxInvalidEvent {
+                                        source: id,
+                                        text: string.clone(),
+                                        reason,
+                                    })
+                                }
+                            },
+                        },
                         _ => {}
                     }
                 }
+                if invalid_text.map_or(false, |invalid_text| invalid_text != *string) {
+                    cmd.entity(id).remove::<Invalid>();
+                }
             }
         }
     }
 
+    /// Records an edit in the `EditHistory` and reports it with an `InputBoxChangedEvent`
+    fn record_edit(
+        source: Entity,
+        kind: EditKind,
+        before: EditSnapshot,
+        string: &str,
+        caret: &Caret,
+        history: &mut EditHistory,
+        event_writer: &mut EventWriter<InputBoxChangedEvent>,
+    ) {
+        if before.text != string {
+            event_writer.send(InputBoxChangedEvent {
+                source,
+                old: before.text.clone(),
+                new: string.to_string(),
+                kind,
+            });
+        }
+        history.record(kind, before, string, caret);
+    }
+
+    /// Removes the selected text, returns false if nothing was selected
+    fn remove_selection(string: &mut String, caret: &mut Caret) -> bool {
+        if let Some(selection) = caret.selection() {
+            string.replace_range(selection.clone(), "");
+            caret.character_index = selection.start;
+            caret.collapse();
+            true
+        } else {
+            false
+        }
+    }
+
     fn insert_char(string: &mut String, index: &mut usize, c: char) {
         string.insert(*index, c);
         *index += c.len_utf8();
     }
 
+    /// Inserts pasted text, skipping the characters that are not accepted
+    fn insert_str(
+        string: &mut String,
+        index: &mut usize,
+        s: &str,
+        mut accepts: impl FnMut(&str, char) -> bool,
+    ) {
+        for c in s.chars() {
+            if accepts(string, c) {
+                insert_char(string, index, c);
+            }
+        }
+    }
+
     fn remove_char(string: &mut String, index: &mut usize) {
         if *index < string.len() {
-            string.remove(*index);
+            let end = next_grapheme_boundary(string, *index);
+            string.replace_range(*index..end, "");
         }
     }
 
-    fn move_left(string: &mut String, index: &mut usize) {
-        loop {
-            if *index == 0 {
-                break;
+    fn move_left(string: &str, index: &mut usize) {
+        *index = previous_grapheme_boundary(string, *index);
+    }
+
+    fn move_right(string: &str, index: &mut usize) {
+        *index = next_grapheme_boundary(string, *index);
+    }
+
+    /// Places the caret under the mouse when an `InputBox` is clicked, and extends the selection
+    /// while the mouse is dragged. Shift+click extends the current selection.
+    #[allow(clippy::too_many_arguments)]
+    pub fn input_box_mouse(
+        mut dragging: Local<Option<Entity>>,
+        windows: Res<Windows>,
+        mouse_button_input: Res<Input<MouseButton>>,
+        keyboard_input: Res<Input<KeyCode>>,
+        query_box: Query<
+            (Entity, &InputBox, &Interaction, Option<&Masked>),
+            Without<InheritedDisabled>,
+        >,
+        query_text: Query<(&Text, &Node, &GlobalTransform)>,
+        mut query_caret: Query<&mut Caret>,
+        text_pipeline: Res<DefaultTextPipeline>,
+    ) {
+        let cursor_position = match windows.get_primary().and_then(|w| w.cursor_position()) {
+            Some(cursor_position) => cursor_position,
+            None => return,
+        };
+        let just_pressed = mouse_button_input.just_pressed(MouseButton::Left);
+        if just_pressed {
+            *dragging = query_box
+                .iter()
+                .find(|(_, _, interaction, _)| matches!(interaction, Interaction::Clicked))
+                .map(|(entity, _, _, _)| entity);
+        } else if !mouse_button_input.pressed(MouseButton::Left) {
+            *dragging = None;
+        }
+        let entity = match *dragging {
+            Some(entity) => entity,
+            None => return,
+        };
+        let (input_box, masked) = match query_box.get(entity) {
+            Ok((_, input_box, _, masked)) => (input_box, masked),
+            Err(_) => {
+                *dragging = None;
+                return;
             }
-            *index -= 1;
-            if string.is_char_boundary(*index) {
-                break;
+        };
+        let (text, node, transform) = query_text.get(input_box.text).unwrap();
+        if let Some(layout_info) = text_pipeline.get_glyphs(&input_box.text) {
+            let string = &text.sections[0].value;
+            let lines = TextLines::new(layout_info, string);
+            let left = transform.translation.x - node.size.x / 2.;
+            let top = transform.translation.y + node.size.y / 2.;
+            let line_height = text.sections[0].style.font_size * LINE_SPACING;
+            let line = ((top - cursor_position.y) / line_height).max(0.) as usize;
+            let index = lines.index_at(line, cursor_position.x - left);
+            let index = DisplayMap::new(masked).to_value(index);
+            let mut caret = query_caret.get_mut(input_box.caret).unwrap();
+            let shift =
+                keyboard_input.pressed(KeyCode::LShift) || keyboard_input.pressed(KeyCode::RShift);
+            let anchor = if just_pressed && !shift {
+                index
+            } else {
+                caret.anchor
+            };
+            if caret.anchor != anchor || caret.character_index != index {
+                caret.anchor = anchor;
+                caret.character_index = index;
             }
         }
     }
 
-    fn move_right(string: &mut String, index: &mut usize) {
-        loop {
-            if *index == string.len() {
-                break;
+    /// Highlights the selected text with one rectangle per line, spawned as children of
+    /// the `InputBox::selection` entity
+    pub fn update_selection(
+        mut cmd: Commands,
+        query_box: Query<(&InputBox, &Focusable, Option<&Masked>)>,
+        query_caret: Query<&Caret>,
+        query_text: Query<&Text>,
+        query_children: Query<&Children>,
+        mut query_rect: Query<(&mut Style, &mut Visible)>,
+        text_pipeline: Res<DefaultTextPipeline>,
+    ) {
+        for (input_box, focusable, masked) in query_box.iter() {
+            let caret = query_caret.get(input_box.caret).unwrap();
+            let display = DisplayMap::new(masked);
+            let text = query_text.get(input_box.text).unwrap();
+            let string = &text.sections[0].value;
+            let font_size = text.sections[0].style.font_size;
+            // Rectangles as (left, line, width)
+            let mut rects = Vec::new();
+            if let (Focusable::Focused, Some(selection), Some(layout_info)) = (
+                focusable,
+                caret.selection().map(|selection| {
+                    display.to_display(selection.start)..display.to_display(selection.end)
+                }),
+                text_pipeline.get_glyphs(&input_box.text),
+            ) {
+                if selection.end <= string.len() {
+                    let lines = TextLines::new(layout_info, string);
+                    for line in lines.line_of(selection.start)..=lines.line_of(selection.end) {
+                        let range = lines.line_range(line);
+                        let start = lines.offset(selection.start.max(range.start));
+                        let end = lines.offset(selection.end.min(range.end));
+                        rects.push((start, line, end - start));
+                    }
+                }
             }
-            *index += 1;
-            if string.is_char_boundary(*index) {
-                break;
+            let children = query_children
+                .get(input_box.selection)
+                .map_or(&[][..], |children| &**children);
+            for (i, &child) in children.iter().enumerate() {
+                if let Ok((mut style, mut visible)) = query_rect.get_mut(child) {
+                    if let Some(&(left, line, width)) = rects.get(i) {
+                        let left = Val::Px(left);
+                        let bottom = Val::Px(line as f32 * font_size * LINE_SPACING);
+                        let size = Size::new(Val::Px(width), Val::Px(font_size));
+                        if style.position.left != left
+                            || style.position.bottom != bottom
+                            || style.size != size
+                        {
+                            style.position.left = left;
+                            style.position.bottom = bottom;
+                            style.size = size;
+                        }
+                        if !visible.is_visible {
+                            visible.is_visible = true;
+                        }
+                    } else if visible.is_visible {
+                        visible.is_visible = false;
+                    }
+                }
+            }
+            if rects.len() > children.len() {
+                cmd.entity(input_box.selection).with_children(|parent| {
+                    for _ in children.len()..rects.len() {
+                        parent.spawn_bundle(selection_rect());
+                    }
+                });
             }
         }
     }
 
+    fn selection_rect() -> NodeBundle {
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                ..Default::default()
+            },
+            material: SELECTION_MATERIAL_HANDLE.typed(),
+            visible: Visible {
+                is_visible: false,
+                is_transparent: true,
+            },
+            focus_policy: FocusPolicy::Pass,
+            ..Default::default()
+        }
+    }
+
+    /// Scroll offset of the content of an `InputBox`, in pixels.
+    /// It is horizontal for a single line, and vertical for a `TextArea`.
+    #[derive(Reflect, Default, Component)]
+    #[reflect(Component)]
+    pub struct TextScroll {
+        pub offset: Vec2,
+    }
+
+    /// Clips the content of the `InputBox`es and scrolls it to keep the caret visible
+    pub fn scroll_to_caret(
+        mut query_box: Query<(
+            &InputBox,
+            &Node,
+            &mut TextScroll,
+            Option<&Masked>,
+            Option<&TextArea>,
+        )>,
+        query_caret: Query<&Caret>,
+        query_text: Query<&Text>,
+        mut query_style: Query<&mut Style>,
+        text_pipeline: Res<DefaultTextPipeline>,
+    ) {
+        for (input_box, node, mut scroll, masked, text_area) in query_box.iter_mut() {
+            let layout_info = match text_pipeline.get_glyphs(&input_box.text) {
+                Some(layout_info) => layout_info,
+                None => continue,
+            };
+            let caret = query_caret.get(input_box.caret).unwrap();
+            let text = query_text.get(input_box.text).unwrap();
+            let index = DisplayMap::new(masked).to_display(caret.character_index);
+            let lines = TextLines::new(layout_info, &text.sections[0].value);
+            let line_height = text.sections[0].style.font_size * LINE_SPACING;
+            let caret_position = Vec2::new(
+                lines.offset(index),
+                lines.line_of(index) as f32 * line_height,
+            );
+            let caret_size = Vec2::new(2., line_height);
+            // The content has a 2px margin inside the box
+            let view = node.size - Vec2::splat(4.);
+            let content = Vec2::new(
+                layout_info.size.width + caret_size.x,
+                lines.line_count() as f32 * line_height,
+            );
+            let mut offset = scroll.offset;
+            offset = offset.min(caret_position);
+            offset = offset.max(caret_position + caret_size - view);
+            offset = offset.min(content - view).max(Vec2::ZERO);
+            // With its spacing a single line is taller than the box, but its glyphs fit: only scroll it
+            // horizontally
+            if text_area.is_none() {
+                offset.y = 0.;
+            }
+            if scroll.offset != offset {
+                scroll.offset = offset;
+            }
+            // Lines are laid out from the top
+            let left = Val::Px(2. - offset.x);
+            let bottom = Val::Px(2. - offset.y);
+            for entity in [input_box.text, input_box.selection] {
+                let mut style = query_style.get_mut(entity).unwrap();
+                if style.position.left != left || style.position.bottom != bottom {
+                    style.position.left = left;
+                    style.position.bottom = bottom;
+                }
+            }
+        }
+    }
+
+    /// Shows the placeholder text while the `InputBox` is empty
+    pub fn show_placeholder(
+        query_box: Query<&InputBox>,
+        query_text: Query<&Text>,
+        mut query_placeholder: Query<&mut Visible>,
+    ) {
+        for input_box in query_box.iter() {
+            let text = query_text.get(input_box.text).unwrap();
+            let is_visible = text.sections[0].value.is_empty();
+            if let Ok(mut visible) = query_placeholder.get_mut(input_box.placeholder) {
+                if visible.is_visible != is_visible {
+                    visible.is_visible = is_visible;
+                }
+            }
+        }
+    }
+
+    /// Empties the `InputBox`es targeted by an `InputBoxClearEvent`.
+    /// Runs before `input_box_set`, so that a box cleared then set in the same frame keeps the
+    /// new content.
     pub fn input_box_clear(
         mut event_reader: EventReader<InputBoxClearEvent>,
-        query_box: Query<&InputBox>,
+        mut query_box: Query<(&InputBox, &mut EditHistory, Option<&mut Masked>)>,
         mut query_text: Query<&mut Text>,
         mut query_caret: Query<&mut Caret>,
+        mut changed_event_writer: EventWriter<InputBoxChangedEvent>,
     ) {
         for event in event_reader.iter() {
-            let input_box = query_box.get(event.target).unwrap();
-            let mut text = query_text.get_mut(input_box.text).unwrap();
-            text.sections[0].value.clear();
-            let mut caret = query_caret.get_mut(input_box.caret).unwrap();
-            caret.character_index = 0;
+            set_content(
+                event.target,
+                "",
+                &mut query_box,
+                &mut query_text,
+                &mut query_caret,
+                &mut changed_event_writer,
+            );
+        }
+    }
+
+    /// Replaces the content of the `InputBox`es targeted by an `InputBoxSetEvent`, in the order
+    /// the events were sent, and moves their caret to the end
+    pub fn input_box_set(
+        mut event_reader: EventReader<InputBoxSetEvent>,
+        mut query_box: Query<(&InputBox, &mut EditHistory, Option<&mut Masked>)>,
+        mut query_text: Query<&mut Text>,
+        mut query_caret: Query<&mut Caret>,
+        mut changed_event_writer: EventWriter<InputBoxChangedEvent>,
+    ) {
+        for event in event_reader.iter() {
+            set_content(
+                event.target,
+                &event.text,
+                &mut query_box,
+                &mut query_text,
+                &mut query_caret,
+                &mut changed_event_writer,
+            );
+        }
+    }
+
+    fn set_content(
+        target: Entity,
+        content: &str,
+        query_box: &mut Query<(&InputBox, &mut EditHistory, Option<&mut Masked>)>,
+        query_text: &mut Query<&mut Text>,
+        query_caret: &mut Query<&mut Caret>,
+        changed_event_writer: &mut EventWriter<InputBoxChangedEvent>,
+    ) {
+        let (input_box, mut history, mut masked) = match query_box.get_mut(target) {
+            Ok(input_box) => input_box,
+            Err(_) => {
+                warn!(
+                    "Cannot set the content of {:?}, it is not an InputBox",
+                    target
+                );
+                return;
+            }
+        };
+        let mut text = query_text.get_mut(input_box.text).unwrap();
+        let string = match masked.as_mut() {
+            Some(masked) => &mut masked.value,
+            None => &mut text.sections[0].value,
+        };
+        let mut caret = query_caret.get_mut(input_box.caret).unwrap();
+        let before = EditSnapshot::new(string, &caret);
+        *string = content.to_string();
+        caret.character_index = string.len();
+        caret.collapse();
+        record_edit(
+            target,
+            EditKind::Set,
+            before,
+            string,
+            &caret,
+            &mut history,
+            changed_event_writer,
+        );
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::super::segmentation::is_grapheme_boundary;
+        use super::*;
+
+        /// Grapheme clusters made of several code points
+        const CLUSTERS: &[&str] = &[
+            // "e" and a combining acute accent
+            "e\u{301}",
+            // Family: man, woman, girl, joined by ZWJ
+            "\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467}",
+            // Thumbs up with a medium skin tone
+            "\u{1f44d}\u{1f3fd}",
+            // Flag of France, a pair of regional indicators
+            "\u{1f1eb}\u{1f1f7}",
+        ];
+
+        #[test]
+        fn caret_steps_over_clusters() {
+            for cluster in CLUSTERS {
+                let string = format!("a{}b", cluster);
+                let end = 1 + cluster.len();
+                let mut index = 1;
+                move_right(&string, &mut index);
+                assert_eq!(index, end, "right over {:?}", cluster);
+                move_left(&string, &mut index);
+                assert_eq!(index, 1, "left over {:?}", cluster);
+            }
+        }
+
+        #[test]
+        fn backspace_removes_clusters() {
+            for cluster in CLUSTERS {
+                let mut string = format!("a{}b", cluster);
+                let mut index = 1 + cluster.len();
+                move_left(&string, &mut index);
+                remove_char(&mut string, &mut index);
+                assert_eq!(string, "ab", "backspace after {:?}", cluster);
+                assert_eq!(index, 1);
+            }
+        }
+
+        #[test]
+        fn delete_removes_clusters() {
+            for cluster in CLUSTERS {
+                let mut string = format!("a{}b", cluster);
+                let mut index = 1;
+                remove_char(&mut string, &mut index);
+                assert_eq!(string, "ab", "delete before {:?}", cluster);
+                assert_eq!(index, 1);
+            }
+        }
+
+        #[test]
+        fn reloaded_masked_value_can_be_edited() {
+            // The value is not saved, but the indices of the caret are
+            let mut masked = Masked::default();
+            let mut caret = Caret {
+                text: Entity::new(0),
+                character_index: 8,
+                anchor: 4,
+            };
+            assert!(!caret.is_inside(&masked.value));
+            caret.clamp_to(&masked.value);
+            assert!(caret.is_inside(&masked.value));
+            assert!(!remove_selection(&mut masked.value, &mut caret));
+            insert_char(&mut masked.value, &mut caret.character_index, 'a');
+            insert_char(&mut masked.value, &mut caret.character_index, 'b');
+            assert_eq!(masked.value(), "ab");
+            move_left(&masked.value, &mut caret.character_index);
+            remove_char(&mut masked.value, &mut caret.character_index);
+            assert_eq!(masked.value(), "a");
+            assert_eq!(caret.character_index, 1);
+        }
+
+        #[test]
+        fn caret_is_clamped_to_a_char_boundary() {
+            let string = "a\u{e9}";
+            let mut caret = Caret {
+                text: Entity::new(0),
+                character_index: 2,
+                anchor: 10,
+            };
+            caret.clamp_to(string);
+            assert_eq!(caret.character_index, 1);
+            assert_eq!(caret.anchor, 3);
+        }
+
+        #[test]
+        fn adjacent_flags_are_separate_clusters() {
+            let flags = "\u{1f1eb}\u{1f1f7}\u{1f1e9}\u{1f1ea}";
+            let mut index = 0;
+            move_right(flags, &mut index);
+            assert_eq!(index, 8);
+            assert!(is_grapheme_boundary(flags, 8));
+            assert!(!is_grapheme_boundary(flags, 4));
+            move_right(flags, &mut index);
+            assert_eq!(index, flags.len());
         }
     }
 }
@@ -252,9 +1173,14 @@ impl InputBox {
             .world_mut()
             .spawn()
             .insert_bundle(TextBundle {
+                // Moved by `scroll_to_caret`
                 style: Style {
-                    align_self: AlignSelf::FlexStart,
-                    margin: Rect::all(Val::Px(2.0)),
+                    position_type: PositionType::Absolute,
+                    position: Rect {
+                        left: Val::Px(2.),
+                        bottom: Val::Px(2.),
+                        ..Default::default()
+                    },
                     size: Size {
                         width: Val::Undefined,
                         height: Val::Px(16.),
@@ -274,16 +1200,85 @@ impl InputBox {
                 focus_policy: FocusPolicy::Pass,
                 ..Default::default()
             })
+            .insert(DisabledColor::new(Color::GRAY))
             .push_children(&[caret])
             .id();
-        wb.world_mut().entity_mut(caret).insert(components::Caret {
-            text,
-            character_index: 0,
-        });
+        wb.world_mut()
+            .entity_mut(caret)
+            .insert(components::Caret {
+                text,
+                character_index: 0,
+                anchor: 0,
+            })
+            .insert(components::CaretBlink::default());
+        // Shown instead of the text while it is empty
+        let placeholder = wb
+            .world_mut()
+            .spawn()
+            .insert_bundle(TextBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    position: Rect {
+                        left: Val::Px(2.),
+                        bottom: Val::Px(2.),
+                        ..Default::default()
+                    },
+                    size: Size {
+                        width: Val::Undefined,
+                        height: Val::Px(16.),
+                    },
+                    ..Default::default()
+                },
+                text: Text::with_section(
+                    String::new(),
+                    TextStyle {
+                        font: wb.default_font.clone(),
+                        font_size: 16.0,
+                        color: Color::GRAY,
+                    },
+                    Default::default(),
+                ),
+                focus_policy: FocusPolicy::Pass,
+                ..Default::default()
+            })
+            .id();
+        // Holds the rectangles of the selection, positioned like the text.
+        // Spawned before the text so that they are drawn behind it.
+        let selection = wb
+            .world_mut()
+            .spawn()
+            .insert_bundle(NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    position: Rect {
+                        left: Val::Px(2.),
+                        bottom: Val::Px(2.),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                visible: Visible {
+                    is_visible: false,
+                    is_transparent: true,
+                },
+                focus_policy: FocusPolicy::Pass,
+                ..Default::default()
+            })
+            .id();
         let base = Base::spawn(wb)
             .insert_bundle(NodeBundle {
                 style: Style {
                     margin: Rect::all(Val::Px(2.0)),
+                    // The text has no size in the layout since it is scrolled
+                    size: Size {
+                        width: Val::Auto,
+                        height: Val::Px(20.),
+                    },
+                    min_size: Size {
+                        width: Val::Px(100.),
+                        height: Val::Auto,
+                    },
+                    overflow: Overflow::Hidden,
                     flex_shrink: 0.,
                     ..Default::default()
                 },
@@ -292,9 +1287,68 @@ impl InputBox {
             .insert(Interaction::default())
             .insert(FocusPolicy::Block)
             .insert(Focusable::default())
-            .insert(components::InputBox { text, caret })
-            .push_children(&[text]);
-        Self { base, text, caret }
+            .insert(InvalidMaterial::new(INVALID_MATERIAL_HANDLE.typed()))
+            .insert(components::EditHistory::default())
+            .insert(components::TextScroll::default())
+            .insert(components::InputBox {
+                text,
+                caret,
+                selection,
+                placeholder,
+            })
+            .push_children(&[placeholder, selection, text]);
+        Self {
+            base,
+            text,
+            caret,
+            selection,
+            placeholder,
+        }
+    }
+
+    /// Sets the hint shown while the `InputBox` is empty
+    pub fn placeholder(self, placeholder: &str) -> Self {
+        if let Some(mut text) = self.builder().world_mut().get_mut::<Text>(self.placeholder) {
+            text.sections[0].value = placeholder.to_string();
+        }
+        self
+    }
+
+    /// Hides the content behind a `MASK` per character, like a password
+    pub fn masked(self) -> Self {
+        self.insert(components::Masked::default())
+    }
+
+    /// Suggests completions from the `SuggestionProvider` registered as `provider` in the
+    /// `SuggestionProviders` resource, in a popup below the `InputBox`
+    pub fn autocomplete(self, provider: &str) -> Self {
+        let popup = self
+            .builder()
+            .world_mut()
+            .spawn()
+            .insert_bundle(NodeBundle {
+                style: Style {
+                    display: Display::None,
+                    position_type: PositionType::Absolute,
+                    flex_direction: FlexDirection::ColumnReverse,
+                    ..Default::default()
+                },
+                visible: Visible {
+                    is_visible: false,
+                    is_transparent: false,
+                },
+                ..Default::default()
+            })
+            .insert(components::SuggestionPopup {
+                input_box: self.root_id(),
+            })
+            .id();
+        self.insert(components::Autocomplete::new(provider, popup))
+            .with_root(|mut root| {
+                if !root.contains::<KeyCapture>() {
+                    root.insert(KeyCapture::default());
+                }
+            })
     }
 
     pub fn text(self, f: impl FnOnce(EntityMut)) -> Self {
@@ -306,6 +1360,12 @@ impl InputBox {
         f(self.builder().world.borrow_mut().entity_mut(self.caret));
         self
     }
+
+    /// The selection is highlighted by rectangles spawned as children of this entity
+    pub fn selection(self, f: impl FnOnce(EntityMut)) -> Self {
+        f(self.builder().world_mut().entity_mut(self.selection));
+        self
+    }
 }
 
 impl Widget for InputBox {
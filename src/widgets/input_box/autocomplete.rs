@@ -0,0 +1,512 @@
+use bevy::{
+    ecs::{
+        entity::{EntityMap, MapEntities, MapEntitiesError},
+        reflect::ReflectMapEntities,
+    },
+    prelude::*,
+    ui::FocusPolicy,
+};
+use std::collections::HashMap;
+
+use super::{
+    components::{InputBox, Masked},
+    EditKind, InputBoxChangedEvent, InputBoxReturnEvent, InputBoxSetEvent,
+    SELECTION_MATERIAL_HANDLE,
+};
+use crate::{FocusLost, Focusable, InheritedDisabled, KeyCapture};
+
+/// Source of the suggestions of an `InputBox` with `Autocomplete`
+pub trait SuggestionProvider: Send + Sync + 'static {
+    /// Completions of `text`, best first
+    fn suggestions(&self, text: &str) -> Vec<String>;
+
+    /// Called when an `InputBox` using this provider is submitted
+    fn submitted(&mut self, _text: &str) {}
+}
+
+impl<F: Fn(&str) -> Vec<String> + Send + Sync + 'static> SuggestionProvider for F {
+    fn suggestions(&self, text: &str) -> Vec<String> {
+        self(text)
+    }
+}
+
+/// Whether `candidate` starts with `text` and is longer, ignoring case
+fn completes(candidate: &str, text: &str) -> bool {
+    let candidate = candidate.to_lowercase();
+    let text = text.to_lowercase();
+    !text.is_empty() && candidate != text && candidate.starts_with(&text)
+}
+
+/// Suggests the entries of a fixed list that complete the text, in order
+pub struct StaticSuggestions(pub Vec<String>);
+
+impl SuggestionProvider for StaticSuggestions {
+    fn suggestions(&self, text: &str) -> Vec<String> {
+        self.0
+            .iter()
+            .filter(|entry| completes(entry, text))
+            .cloned()
+            .collect()
+    }
+}
+
+/// Suggests the previously submitted texts that complete the text, most recent first
+pub struct HistorySuggestions {
+    entries: Vec<String>,
+    limit: usize,
+}
+
+impl HistorySuggestions {
+    /// Remembers at most `limit` texts
+    pub fn new(limit: usize) -> Self {
+        Self {
+            entries: Vec::new(),
+            limit,
+        }
+    }
+}
+
+impl SuggestionProvider for HistorySuggestions {
+    fn suggestions(&self, text: &str) -> Vec<String> {
+        self.entries
+            .iter()
+            .rev()
+            .filter(|entry| completes(entry, text))
+            .cloned()
+            .collect()
+    }
+
+    fn submitted(&mut self, text: &str) {
+        if text.trim().is_empty() {
+            return;
+        }
+        self.entries.retain(|entry| entry != text);
+        self.entries.push(text.to_string());
+        if self.entries.len() > self.limit {
+            self.entries.remove(0);
+        }
+    }
+}
+
+/// Resource holding the `SuggestionProvider`s.
+/// They are referenced by name so that `Autocomplete` can be saved in scenes.
+#[derive(Default)]
+pub struct SuggestionProviders(HashMap<String, Box<dyn SuggestionProvider>>);
+
+impl SuggestionProviders {
+    pub fn add(&mut self, name: impl Into<String>, provider: impl SuggestionProvider) -> &mut Self {
+        self.0.insert(name.into(), Box::new(provider));
+        self
+    }
+}
+
+/// Shows the suggestions of a `SuggestionProvider` in a popup below an `InputBox` while it is
+/// edited. Up/Down select a suggestion, Tab, Enter or a click accept it, and Escape dismisses
+/// the popup. The suggestions are not saved with the scene.
+#[derive(Reflect, Component)]
+#[reflect(Component, MapEntities)]
+pub struct Autocomplete {
+    /// Name of the `SuggestionProvider` in the `SuggestionProviders` resource
+    pub provider: String,
+    /// Maximum number of suggestions shown
+    pub max_suggestions: usize,
+    /// Root node of the popup, which contains a `SuggestionRow` per suggestion
+    pub popup: Entity,
+    #[reflect(ignore)]
+    suggestions: Vec<String>,
+    #[reflect(ignore)]
+    selected: Option<usize>,
+    /// `KeyCapture` of the `InputBox` before the popup opened, restored when it closes
+    #[reflect(ignore)]
+    saved_capture: Option<KeyCapture>,
+}
+
+impl Autocomplete {
+    pub fn new(provider: &str, popup: Entity) -> Self {
+        Self {
+            provider: provider.to_string(),
+            max_suggestions: 8,
+            popup,
+            suggestions: Vec::new(),
+            selected: None,
+            saved_capture: None,
+        }
+    }
+
+    pub fn suggestions(&self) -> &[String] {
+        &self.suggestions
+    }
+
+    pub fn selected(&self) -> Option<&str> {
+        self.selected
+            .and_then(|index| self.suggestions.get(index))
+            .map(String::as_str)
+    }
+
+    fn close(&mut self) {
+        self.suggestions.clear();
+        self.selected = None;
+    }
+}
+
+// I hate this
+impl FromWorld for Autocomplete {
+    fn from_world(_world: &mut bevy::prelude::World) -> Self {
+        Self::new("", Entity::new(u32::MAX))
+    }
+}
+
+// This could be derived eventually
+impl MapEntities for Autocomplete {
+    fn map_entities(&mut self, entity_map: &EntityMap) -> Result<(), MapEntitiesError> {
+        self.popup = entity_map.get(self.popup)?;
+        Ok(())
+    }
+}
+
+/// Root node of the popup of an `Autocomplete`. It is not a child of its `InputBox`, which
+/// clips its content, so it is despawned once the `InputBox` is despawned or loses its
+/// `Autocomplete`.
+#[derive(Reflect, Component)]
+#[reflect(Component, MapEntities)]
+pub struct SuggestionPopup {
+    pub input_box: Entity,
+}
+
+// I hate this
+impl FromWorld for SuggestionPopup {
+    fn from_world(_world: &mut bevy::prelude::World) -> Self {
+        Self {
+            input_box: Entity::new(u32::MAX),
+        }
+    }
+}
+
+// This could be derived eventually
+impl MapEntities for SuggestionPopup {
+    fn map_entities(&mut self, entity_map: &EntityMap) -> Result<(), MapEntitiesError> {
+        self.input_box = entity_map.get(self.input_box)?;
+        Ok(())
+    }
+}
+
+/// Marker component for the rows of the `Autocomplete` popup
+#[derive(Reflect, Default, Component)]
+#[reflect(Component)]
+pub struct SuggestionRow;
+
+/// Updates the suggestions as the `InputBox`es are edited by the user, and handles their selection.
+/// While the popup is open, the `KeyCapture` of the `InputBox` keeps Tab, Escape and the arrow
+/// keys from the focus navigation, and Enter from submitting once a suggestion is selected.
+/// The popup of a disabled `InputBox` is closed.
+/// The hidden values of `Masked` boxes are never given to the providers.
+#[allow(clippy::too_many_arguments)]
+pub fn autocomplete_input(
+    mut changed_events: EventReader<InputBoxChangedEvent>,
+    mut return_events: EventReader<InputBoxReturnEvent>,
+    mut focus_lost_events: EventReader<FocusLost>,
+    keyboard_input: Res<Input<KeyCode>>,
+    mut providers: ResMut<SuggestionProviders>,
+    mut query_box: Query<(Entity, &mut Autocomplete, &Focusable, &mut KeyCapture)>,
+    query_masked: Query<&Masked>,
+    query_disabled: Query<&InheritedDisabled>,
+    query_children: Query<&Children>,
+    query_row: Query<&Interaction, (Changed<Interaction>, With<SuggestionRow>)>,
+    mut set_event_writer: EventWriter<InputBoxSetEvent>,
+) {
+    let hidden = |entity| {
+        query_masked
+            .get(entity)
+            .map_or(false, |masked| !masked.reveal)
+    };
+    for event in changed_events.iter() {
+        if let Ok((_, mut autocomplete, _, _)) = query_box.get_mut(event.source) {
+            autocomplete.close();
+            // Accepted suggestions and other programmatic changes are not completed
+            if event.kind != EditKind::Set && !hidden(event.source) {
+                if let Some(provider) = providers.0.get(&autocomplete.provider) {
+                    let mut suggestions = provider.suggestions(&event.new);
+                    suggestions.truncate(autocomplete.max_suggestions);
+                    autocomplete.suggestions = suggestions;
+                }
+            }
+        }
+    }
+    for event in return_events.iter() {
+        if let Ok((_, mut autocomplete, _, _)) = query_box.get_mut(event.source) {
+            if let Some(provider) = providers
+                .0
+                .get_mut(&autocomplete.provider)
+                .filter(|_| !hidden(event.source))
+            {
+                provider.submitted(&event.text);
+            }
+            autocomplete.close();
+        }
+    }
+    for event in focus_lost_events.iter() {
+        if let Ok((_, mut autocomplete, _, _)) = query_box.get_mut(event.entity) {
+            autocomplete.close();
+        }
+    }
+    for (entity, mut autocomplete, focusable, mut key_capture) in query_box.iter_mut() {
+        if query_disabled.get(entity).is_ok() && !autocomplete.suggestions.is_empty() {
+            autocomplete.close();
+        }
+        let count = autocomplete.suggestions.len();
+        if matches!(focusable, Focusable::Focused) && count > 0 {
+            let clicked = query_children
+                .get(autocomplete.popup)
+                .ok()
+                .and_then(|rows| {
+                    rows.iter()
+                        .take(count)
+                        .position(|&row| matches!(query_row.get(row), Ok(Interaction::Clicked)))
+                });
+            let accepted = if clicked.is_some() {
+                clicked
+            } else if keyboard_input.just_pressed(KeyCode::Tab) {
+                Some(autocomplete.selected.unwrap_or(0))
+            } else if keyboard_input.just_pressed(KeyCode::Return)
+                || keyboard_input.just_pressed(KeyCode::NumpadEnter)
+            {
+                autocomplete.selected
+            } else {
+                None
+            };
+            if let Some(index) = accepted {
+                set_event_writer.send(InputBoxSetEvent {
+                    target: entity,
+                    text: autocomplete.suggestions[index].clone(),
+                });
+                autocomplete.close();
+            } else if keyboard_input.just_pressed(KeyCode::Escape) {
+                autocomplete.close();
+            } else if keyboard_input.just_pressed(KeyCode::Down) {
+                autocomplete.selected = Some(autocomplete.selected.map_or(0, |i| (i + 1) % count));
+            } else if keyboard_input.just_pressed(KeyCode::Up) {
+                autocomplete.selected = Some(
+                    autocomplete
+                        .selected
+                        .map_or(count - 1, |i| (i + count - 1) % count),
+                );
+            }
+        }
+        // Only the keys used by the popup are captured while it is open, and the previous
+        // `KeyCapture` is restored when it closes
+        if !autocomplete.suggestions.is_empty() {
+            if autocomplete.saved_capture.is_none() {
+                autocomplete.saved_capture = Some(*key_capture);
+            }
+            let saved = autocomplete.saved_capture.unwrap_or_default();
+            let capture = KeyCapture {
+                tab: true,
+                escape: true,
+                enter: saved.enter || autocomplete.selected.is_some(),
+                arrows: true,
+            };
+            if *key_capture != capture {
+                *key_capture = capture;
+            }
+        } else if let Some(saved) = autocomplete.saved_capture.take() {
+            *key_capture = saved;
+        }
+    }
+}
+
+/// Despawns the popups whose `InputBox` was despawned or lost its `Autocomplete`
+pub fn despawn_suggestion_popup(
+    mut cmd: Commands,
+    query_popup: Query<(Entity, &SuggestionPopup)>,
+    query_box: Query<&Autocomplete>,
+) {
+    for (entity, popup) in query_popup.iter() {
+        if query_box
+            .get(popup.input_box)
+            .map_or(true, |autocomplete| autocomplete.popup != entity)
+        {
+            cmd.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+/// Shows the suggestions in the popup, right below their `InputBox`
+pub fn update_suggestion_popup(
+    mut cmd: Commands,
+    windows: Res<Windows>,
+    query_box: Query<(&InputBox, &Autocomplete, &Node, &GlobalTransform)>,
+    mut query_popup: Query<
+        (&mut Style, &mut Visible, Option<&Children>),
+        (With<SuggestionPopup>, Without<SuggestionRow>, Without<Text>),
+    >,
+    mut query_row: Query<
+        (
+            &mut Style,
+            &mut Visible,
+            &mut Handle<ColorMaterial>,
+            &Children,
+        ),
+        With<SuggestionRow>,
+    >,
+    mut query_text: Query<(&mut Text, &mut Visible), Without<SuggestionRow>>,
+) {
+    let window_height = windows.get_primary().map_or(0., |window| window.height());
+    for (input_box, autocomplete, node, transform) in query_box.iter() {
+        let (mut style, mut visible, rows) = match query_popup.get_mut(autocomplete.popup) {
+            Ok(popup) => popup,
+            Err(_) => continue,
+        };
+        let suggestions = &autocomplete.suggestions;
+        // Hidden nodes are still laid out and block the mouse, so they are also removed
+        // from the layout
+        let display = |is_visible: bool| {
+            if is_visible {
+                Display::Flex
+            } else {
+                Display::None
+            }
+        };
+        let is_visible = !suggestions.is_empty();
+        if visible.is_visible != is_visible {
+            visible.is_visible = is_visible;
+        }
+        // The popup is a root node, and the bottom of a node is measured from the top of its
+        // parent, here the window
+        let left = Val::Px(transform.translation.x - node.size.x / 2.);
+        let bottom = Val::Px(window_height - (transform.translation.y - node.size.y / 2.));
+        let width = Val::Px(node.size.x);
+        if style.display != display(is_visible)
+            || style.position.left != left
+            || style.position.bottom != bottom
+            || style.size.width != width
+        {
+            style.display = display(is_visible);
+            style.position.left = left;
+            style.position.bottom = bottom;
+            style.size.width = width;
+        }
+        let rows: &[Entity] = rows.map_or(&[], |rows| &rows[..]);
+        for (i, &row) in rows.iter().enumerate() {
+            if let Ok((mut style, mut visible, mut material, children)) = query_row.get_mut(row) {
+                let suggestion = suggestions.get(i);
+                let is_visible = suggestion.is_some();
+                if style.display != display(is_visible) {
+                    style.display = display(is_visible);
+                }
+                if visible.is_visible != is_visible {
+                    visible.is_visible = is_visible;
+                }
+                let resolved = if autocomplete.selected == Some(i) {
+                    SELECTION_MATERIAL_HANDLE.typed()
+                } else {
+                    Handle::default()
+                };
+                if *material != resolved {
+                    *material = resolved;
+                }
+                for &child in children.iter() {
+                    if let Ok((mut text, mut visible)) = query_text.get_mut(child) {
+                        if let Some(suggestion) = suggestion {
+                            if text.sections[0].value != *suggestion {
+                                text.sections[0].value = suggestion.clone();
+                            }
+                        }
+                        if visible.is_visible != is_visible {
+                            visible.is_visible = is_visible;
+                        }
+                    }
+                }
+            }
+        }
+        if suggestions.len() > rows.len() {
+            let text_style = match query_text.get(input_box.text) {
+                Ok((text, _)) => text.sections[0].style.clone(),
+                Err(_) => continue,
+            };
+            cmd.entity(autocomplete.popup).with_children(|parent| {
+                for _ in rows.len()..suggestions.len() {
+                    spawn_row(parent, text_style.clone());
+                }
+            });
+        }
+    }
+}
+
+/// Draws the open popups over the rest of the UI. The depth of the UI root nodes depends on their
+/// iteration order, so a popup could be covered by a root spawned later (e.g. by a scene). Its
+/// nodes are lifted above all the others once the transforms are propagated.
+pub fn raise_suggestion_popup(
+    query_box: Query<&Autocomplete>,
+    query_children: Query<&Children>,
+    mut query_node: Query<(Entity, &mut GlobalTransform), With<Node>>,
+) {
+    for autocomplete in query_box.iter() {
+        if autocomplete.suggestions.is_empty() {
+            continue;
+        }
+        let mut popup = vec![autocomplete.popup];
+        let mut i = 0;
+        while i < popup.len() {
+            if let Ok(children) = query_children.get(popup[i]) {
+                popup.extend(children.iter());
+            }
+            i += 1;
+        }
+        let bottom = match query_node.get_mut(autocomplete.popup) {
+            Ok((_, transform)) => transform.translation.z,
+            Err(_) => continue,
+        };
+        let top = query_node
+            .iter_mut()
+            .filter(|(entity, _)| !popup.contains(entity))
+            .map(|(_, transform)| transform.translation.z)
+            .fold(f32::MIN, f32::max);
+        // Same step as between nested UI nodes
+        let lift = top - bottom + 0.001;
+        if lift > 0. {
+            for &entity in &popup {
+                if let Ok((_, mut transform)) = query_node.get_mut(entity) {
+                    transform.translation.z += lift;
+                }
+            }
+        }
+    }
+}
+
+fn spawn_row(parent: &mut ChildBuilder, text_style: TextStyle) {
+    parent
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                display: Display::None,
+                padding: Rect::all(Val::Px(2.)),
+                flex_shrink: 0.,
+                ..Default::default()
+            },
+            visible: Visible {
+                is_visible: false,
+                is_transparent: true,
+            },
+            ..Default::default()
+        })
+        .insert(Interaction::default())
+        .insert(FocusPolicy::Block)
+        .insert(SuggestionRow)
+        .with_children(|row| {
+            row.spawn_bundle(TextBundle {
+                style: Style {
+                    size: Size {
+                        width: Val::Undefined,
+                        height: Val::Px(text_style.font_size),
+                    },
+                    ..Default::default()
+                },
+                text: Text::with_section(String::new(), text_style, Default::default()),
+                visible: Visible {
+                    is_visible: false,
+                    is_transparent: true,
+                },
+                focus_policy: FocusPolicy::Pass,
+                ..Default::default()
+            });
+        });
+}
@@ -0,0 +1,159 @@
+use bevy::prelude::*;
+use std::collections::VecDeque;
+
+use super::components::Caret;
+
+/// What an edit of an `InputBox` did
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EditKind {
+    /// Typed characters
+    Insert,
+    /// Typed characters over a selection
+    Replace,
+    /// Removed characters with Backspace or Delete
+    Delete,
+    Cut,
+    Paste,
+    /// Content changed by an event, like `InputBoxSetEvent` or `InputBoxClearEvent`
+    Set,
+    /// Reverted with Ctrl+Z, never recorded in the `EditHistory`
+    Undo,
+    /// Re-applied with Ctrl+Shift+Z or Ctrl+Y, never recorded in the `EditHistory`
+    Redo,
+}
+
+/// Clears the `EditHistory` of an `InputBox`
+pub struct InputBoxClearHistoryEvent {
+    pub target: Entity,
+}
+
+/// State of an `InputBox` before or after an edit
+#[derive(Clone)]
+pub struct EditSnapshot {
+    pub text: String,
+    pub character_index: usize,
+    pub anchor: usize,
+}
+
+impl EditSnapshot {
+    pub fn new(text: &str, caret: &Caret) -> Self {
+        Self {
+            text: text.to_string(),
+            character_index: caret.character_index,
+            anchor: caret.anchor,
+        }
+    }
+
+    fn restore(self, text: &mut String, caret: &mut Caret) {
+        *text = self.text;
+        caret.character_index = self.character_index;
+        caret.anchor = self.anchor;
+    }
+}
+
+/// Bounded undo/redo history of an `InputBox`, used with Ctrl+Z, Ctrl+Shift+Z and Ctrl+Y.
+/// Consecutive typing is coalesced into a single step, until the caret is moved.
+/// The steps are not saved with the scene.
+#[derive(Reflect, Component)]
+#[reflect(Component)]
+pub struct EditHistory {
+    /// Maximum number of steps that can be undone
+    pub limit: usize,
+    #[reflect(ignore)]
+    undo: VecDeque<EditSnapshot>,
+    #[reflect(ignore)]
+    redo: Vec<EditSnapshot>,
+    // Kind of the last edit and caret position after it, to coalesce typing
+    #[reflect(ignore)]
+    last: Option<(EditKind, usize)>,
+}
+
+impl Default for EditHistory {
+    fn default() -> Self {
+        Self::new(100)
+    }
+}
+
+impl EditHistory {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            undo: VecDeque::new(),
+            redo: Vec::new(),
+            last: None,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+        self.last = None;
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Records an edit from its previous state `before` to the current `text` and `caret`
+    pub(crate) fn record(
+        &mut self,
+        kind: EditKind,
+        before: EditSnapshot,
+        text: &str,
+        caret: &Caret,
+    ) {
+        if before.text == text {
+            return;
+        }
+        let coalesce = kind == EditKind::Insert
+            && before.anchor == before.character_index
+            && matches!(self.last, Some((EditKind::Insert, index)) if index == before.character_index);
+        if !coalesce {
+            self.undo.push_back(before);
+            while self.undo.len() > self.limit {
+                self.undo.pop_front();
+            }
+        }
+        self.redo.clear();
+        self.last = Some((kind, caret.character_index));
+    }
+
+    /// Reverts the last step, returns false if there is nothing to undo
+    pub(crate) fn undo(&mut self, text: &mut String, caret: &mut Caret) -> bool {
+        if let Some(snapshot) = self.undo.pop_back() {
+            self.redo.push(EditSnapshot::new(text, caret));
+            snapshot.restore(text, caret);
+            self.last = None;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Re-applies the last undone step, returns false if there is nothing to redo
+    pub(crate) fn redo(&mut self, text: &mut String, caret: &mut Caret) -> bool {
+        if let Some(snapshot) = self.redo.pop() {
+            self.undo.push_back(EditSnapshot::new(text, caret));
+            snapshot.restore(text, caret);
+            self.last = None;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+pub fn input_box_clear_history(
+    mut event_reader: EventReader<InputBoxClearHistoryEvent>,
+    mut query_history: Query<&mut EditHistory>,
+) {
+    for event in event_reader.iter() {
+        if let Ok(mut history) = query_history.get_mut(event.target) {
+            history.clear();
+        }
+    }
+}
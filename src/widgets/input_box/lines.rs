@@ -0,0 +1,130 @@
+use bevy::text::TextLayoutInfo;
+use std::ops::Range;
+
+use super::segmentation::{is_grapheme_boundary, previous_grapheme_boundary};
+
+/// Height of a line relative to the font size, from the metrics of the default font
+pub const LINE_SPACING: f32 = 1.2;
+
+/// Visual lines of a text laid out by the `DefaultTextPipeline`, split on new lines and soft wraps.
+/// Lines are counted from the top, offsets are relative to the left of the text.
+pub struct TextLines<'a> {
+    layout_info: &'a TextLayoutInfo,
+    string: &'a str,
+    // Byte ranges of the lines, without the new line characters and the whitespace where
+    // a line is wrapped
+    ranges: Vec<Range<usize>>,
+}
+
+impl<'a> TextLines<'a> {
+    pub fn new(layout_info: &'a TextLayoutInfo, string: &'a str) -> Self {
+        // Glyphs go back to the left when a line is wrapped
+        let mut wraps = layout_info
+            .glyphs
+            .windows(2)
+            .filter(|pair| pair[1].position.x < pair[0].position.x)
+            .map(|pair| (pair[0].byte_index, pair[1].byte_index))
+            .filter(|&(previous, next)| {
+                previous < next
+                    && string.is_char_boundary(next)
+                    && string
+                        .get(previous..next)
+                        .map_or(false, |between| !between.contains('\n'))
+            })
+            .map(|(_, next)| next)
+            .peekable();
+        let mut ranges = Vec::new();
+        let mut start = 0;
+        for (index, c) in string.char_indices() {
+            while let Some(&wrap) = wraps.peek() {
+                if wrap > index {
+                    break;
+                }
+                if wrap > start {
+                    ranges.push(start..previous_grapheme_boundary(string, wrap));
+                    start = wrap;
+                }
+                wraps.next();
+            }
+            if c == '\n' {
+                ranges.push(start..index);
+                start = index + 1;
+            }
+        }
+        ranges.push(start..string.len());
+        Self {
+            layout_info,
+            string,
+            ranges,
+        }
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.ranges.len()
+    }
+
+    /// Byte range of a line, without its line break
+    pub fn line_range(&self, line: usize) -> Range<usize> {
+        self.ranges[line.min(self.ranges.len() - 1)].clone()
+    }
+
+    /// Line that contains the boundary before the byte `index`
+    pub fn line_of(&self, index: usize) -> usize {
+        self.ranges
+            .iter()
+            .rposition(|range| range.start <= index)
+            .unwrap_or(0)
+    }
+
+    /// Horizontal offset of the boundary before the byte `index`
+    pub fn offset(&self, index: usize) -> f32 {
+        // The layout size includes the trailing whitespaces, which have no glyph
+        if self.ranges.len() == 1 && index >= self.string.len() {
+            return self.layout_info.size.width;
+        }
+        let range = self.line_range(self.line_of(index));
+        // Whitespaces have no glyph, fallback on the end of the previous glyph
+        let mut offset = 0.;
+        for glyph in &self.layout_info.glyphs {
+            if !range.contains(&glyph.byte_index) {
+                continue;
+            }
+            if glyph.byte_index == index {
+                return glyph.position.x - glyph.size.x / 2.;
+            } else if glyph.byte_index < index {
+                offset = glyph.position.x + glyph.size.x / 2.;
+            }
+        }
+        offset
+    }
+
+    /// Byte index of the boundary of `line` nearest to the horizontal offset `x`
+    pub fn index_at(&self, line: usize, x: f32) -> usize {
+        let range = self.line_range(line);
+        let mut nearest = (range.start, (x - self.offset(range.start)).abs());
+        let mut candidate = |index: usize, offset: f32| {
+            // Glyphs of combining characters are inside a grapheme cluster
+            if (x - offset).abs() < nearest.1
+                && range.contains(&index)
+                && is_grapheme_boundary(self.string, index)
+            {
+                nearest = (index, (x - offset).abs());
+            }
+        };
+        for glyph in &self.layout_info.glyphs {
+            let start = glyph.byte_index;
+            let end = self
+                .string
+                .get(start..)
+                .and_then(|s| s.chars().next())
+                .map_or(start, |c| start + c.len_utf8());
+            candidate(start, glyph.position.x - glyph.size.x / 2.);
+            candidate(end, glyph.position.x + glyph.size.x / 2.);
+        }
+        let end = range.end;
+        if (x - self.offset(end)).abs() < nearest.1 {
+            nearest.0 = end;
+        }
+        nearest.0
+    }
+}
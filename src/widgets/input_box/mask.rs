@@ -0,0 +1,113 @@
+use bevy::prelude::*;
+use unicode_segmentation::UnicodeSegmentation;
+
+use super::components::InputBox;
+
+/// Character shown instead of each grapheme cluster of a masked `InputBox`
+pub const MASK: char = '\u{2022}';
+
+/// Hides the content of an `InputBox`, like a password: its `Text` shows a `MASK` per character
+/// unless the value is revealed. The value is kept in this component instead of the `Text`,
+/// and is not reflected so that it never appears in a saved scene. While it is revealed it is also
+/// in the `Text`, so `export_scene` and `print_all` mask it while they write the world out.
+#[derive(Reflect, Default, Component)]
+#[reflect(Component)]
+pub struct Masked {
+    /// Shows the value instead of the masks
+    pub reveal: bool,
+    #[reflect(ignore)]
+    pub(crate) value: String,
+}
+
+impl Masked {
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+}
+
+/// Converts byte indices between the value of an `InputBox` and its `Text`,
+/// which has a `MASK` per grapheme cluster of the value while it is hidden
+#[derive(Clone, Copy)]
+pub(crate) struct DisplayMap<'a> {
+    hidden: Option<&'a str>,
+}
+
+impl<'a> DisplayMap<'a> {
+    pub fn new(masked: Option<&'a Masked>) -> Self {
+        Self {
+            hidden: masked
+                .filter(|masked| !masked.reveal)
+                .map(|masked| masked.value.as_str()),
+        }
+    }
+
+    /// Byte index in the `Text` of the byte `index` of the value
+    pub fn to_display(self, index: usize) -> usize {
+        match self.hidden {
+            Some(value) => {
+                let before = value.get(..index).unwrap_or(value);
+                before.graphemes(true).count() * MASK.len_utf8()
+            }
+            None => index,
+        }
+    }
+
+    /// Byte index in the value of the byte `index` of the `Text`
+    pub fn to_value(self, index: usize) -> usize {
+        match self.hidden {
+            Some(value) => value
+                .grapheme_indices(true)
+                .nth(index / MASK.len_utf8())
+                .map_or(value.len(), |(start, _)| start),
+            None => index,
+        }
+    }
+}
+
+fn mask(value: &str) -> String {
+    value.graphemes(true).map(|_| MASK).collect()
+}
+
+/// Shows the value of the `Masked` `InputBox`es, or a `MASK` per grapheme cluster
+pub fn update_masked_text(
+    query_box: Query<(&InputBox, &Masked), Changed<Masked>>,
+    mut query_text: Query<&mut Text>,
+) {
+    for (input_box, masked) in query_box.iter() {
+        let display = if masked.reveal {
+            masked.value.clone()
+        } else {
+            mask(&masked.value)
+        };
+        let mut text = query_text.get_mut(input_box.text).unwrap();
+        if text.sections[0].value != display {
+            text.sections[0].value = display;
+        }
+    }
+}
+
+/// Masks the `Text` of the revealed `Masked` values while the world is exported, without changing
+/// `Masked::reveal`. Returns the revealed texts, to give back to `restore_masked_values`.
+pub(crate) fn conceal_masked_values(world: &mut World) -> Vec<(Entity, String)> {
+    let revealed = world
+        .query::<(&InputBox, &Masked)>()
+        .iter(world)
+        .filter(|(_, masked)| masked.reveal)
+        .map(|(input_box, masked)| (input_box.text, masked.value.clone()))
+        .collect::<Vec<_>>();
+    for (text, value) in revealed.iter() {
+        if let Some(mut text) = world.get_mut::<Text>(*text) {
+            text.sections[0].value = mask(value);
+        }
+    }
+    revealed
+}
+
+/// Shows the values masked by `conceal_masked_values` again
+pub(crate) fn restore_masked_values(world: &mut World, revealed: Vec<(Entity, String)>) {
+    for (text, value) in revealed {
+        if let Some(mut text) = world.get_mut::<Text>(text) {
+            text.sections[0].value = value;
+        }
+    }
+}
@@ -0,0 +1,112 @@
+//! Text boundaries used to move the caret, independent from the ECS.
+//! All indices are byte indices.
+
+use unicode_segmentation::{GraphemeCursor, UnicodeSegmentation};
+
+/// Words are the segments that contain letters or digits, as opposed to whitespaces and punctuation
+fn is_word(segment: &str) -> bool {
+    segment.chars().any(char::is_alphanumeric)
+}
+
+/// Start of the word before `index`, or of the word containing it (Ctrl+Left)
+pub fn previous_word_boundary(string: &str, index: usize) -> usize {
+    string
+        .split_word_bound_indices()
+        .take_while(|(start, _)| *start < index)
+        .filter(|(_, segment)| is_word(segment))
+        .last()
+        .map_or(0, |(start, _)| start)
+}
+
+/// End of the word after `index`, or of the word containing it (Ctrl+Right)
+pub fn next_word_boundary(string: &str, index: usize) -> usize {
+    string
+        .split_word_bound_indices()
+        .map(|(start, segment)| (start + segment.len(), segment))
+        .find(|(end, segment)| *end > index && is_word(segment))
+        .map_or(string.len(), |(end, _)| end)
+}
+
+/// Whether `index` is between two extended grapheme clusters, which are the characters as perceived
+/// by the user (e.g. a letter and its combining accents, an emoji with modifiers, or a flag)
+pub fn is_grapheme_boundary(string: &str, index: usize) -> bool {
+    string.is_char_boundary(index)
+        && GraphemeCursor::new(index, string.len(), true)
+            .is_boundary(string, 0)
+            .unwrap_or(false)
+}
+
+/// Start of the grapheme cluster before `index`
+pub fn previous_grapheme_boundary(string: &str, index: usize) -> usize {
+    GraphemeCursor::new(index, string.len(), true)
+        .prev_boundary(string, 0)
+        .ok()
+        .flatten()
+        .unwrap_or(0)
+}
+
+/// End of the grapheme cluster after `index`
+pub fn next_grapheme_boundary(string: &str, index: usize) -> usize {
+    GraphemeCursor::new(index, string.len(), true)
+        .next_boundary(string, 0)
+        .ok()
+        .flatten()
+        .unwrap_or_else(|| string.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn word_boundaries_skip_punctuation_runs() {
+        let string = "foo!?bar";
+        assert_eq!(next_word_boundary(string, 3), 8);
+        assert_eq!(next_word_boundary(string, 4), 8);
+        assert_eq!(previous_word_boundary(string, 5), 0);
+        assert_eq!(previous_word_boundary(string, 8), 5);
+    }
+
+    #[test]
+    fn word_boundaries_skip_several_spaces() {
+        let string = "foo   bar";
+        assert_eq!(next_word_boundary(string, 3), 9);
+        assert_eq!(next_word_boundary(string, 4), 9);
+        assert_eq!(previous_word_boundary(string, 6), 0);
+        assert_eq!(previous_word_boundary(string, 5), 0);
+        assert_eq!(previous_word_boundary(string, 9), 6);
+    }
+
+    #[test]
+    fn word_boundaries_at_the_ends() {
+        let string = "foo bar";
+        assert_eq!(previous_word_boundary(string, 0), 0);
+        assert_eq!(next_word_boundary(string, 0), 3);
+        assert_eq!(previous_word_boundary(string, string.len()), 4);
+        assert_eq!(next_word_boundary(string, string.len()), string.len());
+        assert_eq!(previous_word_boundary("", 0), 0);
+        assert_eq!(next_word_boundary("", 0), 0);
+    }
+
+    #[test]
+    fn word_boundaries_inside_a_word() {
+        let string = "foo bar";
+        assert_eq!(previous_word_boundary(string, 2), 0);
+        assert_eq!(next_word_boundary(string, 1), 3);
+        assert_eq!(previous_word_boundary(string, 5), 4);
+        assert_eq!(next_word_boundary(string, 5), 7);
+    }
+
+    #[test]
+    fn word_boundaries_of_non_ascii_words() {
+        // "é" and "ö" are two bytes long
+        let string = "héllo wörld";
+        assert_eq!(next_word_boundary(string, 0), 6);
+        assert_eq!(next_word_boundary(string, 6), 13);
+        assert_eq!(previous_word_boundary(string, 13), 7);
+        assert_eq!(previous_word_boundary(string, 7), 0);
+        let string = "привет мир";
+        assert_eq!(next_word_boundary(string, 0), 12);
+        assert_eq!(previous_word_boundary(string, string.len()), 13);
+    }
+}
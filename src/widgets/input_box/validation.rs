@@ -0,0 +1,290 @@
+use bevy::prelude::*;
+use std::{collections::HashMap, ops::Range};
+
+/// Custom rule of an `InputFilter`, registered by name in the `InputRules` resource
+pub trait CharFilter: Send + Sync + 'static {
+    fn accepts(&self, c: char) -> bool;
+}
+
+impl<F: Fn(char) -> bool + Send + Sync + 'static> CharFilter for F {
+    fn accepts(&self, c: char) -> bool {
+        self(c)
+    }
+}
+
+/// Custom rule of an `InputValidation`, registered by name in the `InputRules` resource.
+/// Returns the reason why the text is invalid.
+pub trait TextValidator: Send + Sync + 'static {
+    fn validate(&self, text: &str) -> Result<(), String>;
+}
+
+impl<F: Fn(&str) -> Result<(), String> + Send + Sync + 'static> TextValidator for F {
+    fn validate(&self, text: &str) -> Result<(), String> {
+        self(text)
+    }
+}
+
+/// Custom filters and validators of the `InputBox`es.
+/// They are referenced by name so that `InputFilter` and `InputValidation` can be saved in scenes.
+#[derive(Default)]
+pub struct InputRules {
+    filters: HashMap<String, Box<dyn CharFilter>>,
+    validators: HashMap<String, Box<dyn TextValidator>>,
+}
+
+impl InputRules {
+    pub fn add_filter(&mut self, name: impl Into<String>, filter: impl CharFilter) -> &mut Self {
+        self.filters.insert(name.into(), Box::new(filter));
+        self
+    }
+
+    pub fn add_validator(
+        &mut self,
+        name: impl Into<String>,
+        validator: impl TextValidator,
+    ) -> &mut Self {
+        self.validators.insert(name.into(), Box::new(validator));
+        self
+    }
+}
+
+/// Why the content of an `InputBox` failed its `InputValidation`
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum InvalidReason {
+    /// The text is empty or only contains whitespace
+    Empty,
+    /// The text has fewer characters than the minimum
+    TooShort(usize),
+    /// Rejected by a custom `TextValidator`
+    Custom(String),
+}
+
+/// Sent instead of the `InputBoxReturnEvent` when the content fails its `InputValidation`
+pub struct InputBoxInvalidEvent {
+    pub source: Entity,
+    pub text: String,
+    pub reason: InvalidReason,
+}
+
+/// Restricts the characters that can be typed or pasted in an `InputBox`
+#[derive(Reflect, Default, Component)]
+#[reflect(Component)]
+pub struct InputFilter {
+    /// Accepted characters, written like the inside of a regex character class: single characters
+    /// and ranges like `a-z`, negated by a leading `^`. A `-` is literal at the start or the end.
+    /// All characters are accepted if it is empty.
+    pub allowed: String,
+    /// Maximum number of characters, unlimited if 0
+    pub max_length: usize,
+    /// Name of a `CharFilter` registered in `InputRules`, ignored if it is empty or not registered
+    pub custom: String,
+}
+
+impl InputFilter {
+    pub fn allowed(allowed: &str) -> Self {
+        Self {
+            allowed: allowed.to_string(),
+            ..Default::default()
+        }
+    }
+
+    pub fn digits() -> Self {
+        Self::allowed("0-9")
+    }
+
+    pub fn max_length(max_length: usize) -> Self {
+        Self {
+            max_length,
+            ..Default::default()
+        }
+    }
+
+    pub fn custom(name: &str) -> Self {
+        Self {
+            custom: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Whether `c` can be inserted in a text of `length` characters
+    pub(crate) fn accepts(&self, rules: &InputRules, length: usize, c: char) -> bool {
+        (self.max_length == 0 || length < self.max_length)
+            && (self.allowed.is_empty() || in_class(&self.allowed, c))
+            && rules
+                .filters
+                .get(&self.custom)
+                .map_or(true, |filter| filter.accepts(c))
+    }
+}
+
+/// Number of characters of `string` that are kept when the `selection` is replaced by typed text
+pub(crate) fn length_without(string: &str, selection: Option<Range<usize>>) -> usize {
+    string.chars().count() - selection.map_or(0, |selection| string[selection].chars().count())
+}
+
+fn in_class(class: &str, c: char) -> bool {
+    let (negated, class) = match class.strip_prefix('^') {
+        Some(class) => (true, class),
+        None => (false, class),
+    };
+    let chars = class.chars().collect::<Vec<_>>();
+    let mut found = false;
+    let mut i = 0;
+    while i < chars.len() {
+        if i + 2 < chars.len() && chars[i + 1] == '-' {
+            found |= (chars[i]..=chars[i + 2]).contains(&c);
+            i += 3;
+        } else {
+            found |= chars[i] == c;
+            i += 1;
+        }
+    }
+    found != negated
+}
+
+/// Checks the content of an `InputBox` when it is submitted.
+/// An empty text is only rejected if it is `required`.
+#[derive(Reflect, Default, Component)]
+#[reflect(Component)]
+pub struct InputValidation {
+    pub required: bool,
+    /// Minimum number of characters
+    pub min_length: usize,
+    /// Name of a `TextValidator` registered in `InputRules`, ignored if it is empty or not registered
+    pub custom: String,
+}
+
+impl InputValidation {
+    pub fn required() -> Self {
+        Self {
+            required: true,
+            ..Default::default()
+        }
+    }
+
+    pub fn custom(name: &str) -> Self {
+        Self {
+            custom: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn validate(&self, rules: &InputRules, text: &str) -> Result<(), InvalidReason> {
+        if text.trim().is_empty() {
+            return if self.required {
+                Err(InvalidReason::Empty)
+            } else {
+                Ok(())
+            };
+        }
+        if text.chars().count() < self.min_length {
+            return Err(InvalidReason::TooShort(self.min_length));
+        }
+        match rules.validators.get(&self.custom) {
+            Some(validator) => validator.validate(text).map_err(InvalidReason::Custom),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn class_ranges() {
+        assert!(in_class("a-z0-9", 'm'));
+        assert!(in_class("a-z0-9", '0'));
+        assert!(in_class("a-z0-9", '9'));
+        assert!(!in_class("a-z0-9", 'M'));
+        assert!(!in_class("a-z0-9", '-'));
+        assert!(in_class("a-cx", 'x'));
+        assert!(!in_class("a-cx", 'd'));
+    }
+
+    #[test]
+    fn class_literal_dash() {
+        assert!(in_class("-0-9", '-'));
+        assert!(in_class("-0-9", '5'));
+        assert!(in_class("0-9-", '-'));
+        assert!(in_class("0-9.-", '.'));
+        assert!(!in_class("0-9.-", '+'));
+    }
+
+    #[test]
+    fn class_negation() {
+        assert!(!in_class("^0-9", '5'));
+        assert!(in_class("^0-9", 'a'));
+        assert!(in_class("^ ", 'a'));
+        assert!(!in_class("^ ", ' '));
+        // Only a leading `^` negates
+        assert!(in_class("a^", '^'));
+    }
+
+    #[test]
+    fn filter_max_length() {
+        let rules = InputRules::default();
+        let filter = InputFilter::max_length(3);
+        assert!(filter.accepts(&rules, length_without("ab", None), 'c'));
+        assert!(!filter.accepts(&rules, length_without("abc", None), 'd'));
+        // Replacing a selection frees its characters
+        assert!(filter.accepts(&rules, length_without("abc", Some(1..2)), 'd'));
+        assert!(filter.accepts(&rules, length_without("abc", Some(0..3)), 'd'));
+        // Lengths are counted in characters, not bytes
+        assert!(filter.accepts(&rules, length_without("éé", None), 'é'));
+        assert!(!filter.accepts(&rules, length_without("ééé", Some(2..2)), 'é'));
+        assert!(filter.accepts(&rules, length_without("ééé", Some(2..4)), 'é'));
+    }
+
+    #[test]
+    fn filter_rules() {
+        let mut rules = InputRules::default();
+        rules.add_filter("vowels", |c: char| "aeiou".contains(c));
+        let filter = InputFilter {
+            allowed: "a-z".to_string(),
+            max_length: 0,
+            custom: "vowels".to_string(),
+        };
+        assert!(filter.accepts(&rules, 100, 'e'));
+        assert!(!filter.accepts(&rules, 0, 'b'));
+        assert!(!filter.accepts(&rules, 0, 'E'));
+        assert!(InputFilter::custom("unknown").accepts(&rules, 0, 'b'));
+    }
+
+    #[test]
+    fn validation() {
+        let mut rules = InputRules::default();
+        rules.add_validator("no_spaces", |text: &str| {
+            if text.contains(' ') {
+                Err("no spaces".to_string())
+            } else {
+                Ok(())
+            }
+        });
+        assert_eq!(InputValidation::default().validate(&rules, ""), Ok(()));
+        assert_eq!(
+            InputValidation::required().validate(&rules, "  "),
+            Err(InvalidReason::Empty)
+        );
+        assert_eq!(InputValidation::required().validate(&rules, "a"), Ok(()));
+        let min_length = InputValidation {
+            min_length: 3,
+            ..Default::default()
+        };
+        assert_eq!(
+            min_length.validate(&rules, "ab"),
+            Err(InvalidReason::TooShort(3))
+        );
+        assert_eq!(min_length.validate(&rules, "abc"), Ok(()));
+        // An optional empty text is not too short
+        assert_eq!(min_length.validate(&rules, ""), Ok(()));
+        assert_eq!(
+            InputValidation::custom("no_spaces").validate(&rules, "a b"),
+            Err(InvalidReason::Custom("no spaces".to_string()))
+        );
+        assert_eq!(
+            InputValidation::custom("no_spaces").validate(&rules, "ab"),
+            Ok(())
+        );
+    }
+}
@@ -1,18 +1,35 @@
 mod base;
 mod button;
+mod check_box;
 mod from_scene;
 mod input_box;
 mod label;
+mod radio_button;
+mod spin_box;
 mod stack;
+mod text_area;
 
 pub use base::Base;
 pub use button::Button;
+pub use check_box::{CheckBox, CheckBoxChanged};
 pub use from_scene::FromScene;
-pub use input_box::{InputBox, InputBoxClearEvent, InputBoxReturnEvent};
+pub use input_box::{
+    CharFilter, EditKind, EditSnapshot, HistorySuggestions, InputBox, InputBoxChangedEvent,
+    InputBoxClearEvent, InputBoxClearHistoryEvent, InputBoxInvalidEvent, InputBoxReturnEvent,
+    InputBoxSetEvent, InputRules, InvalidReason, StaticSuggestions, SuggestionProvider,
+    SuggestionProviders, TextValidator, INVALID_MATERIAL_HANDLE, MASK, SELECTION_MATERIAL_HANDLE,
+};
 pub use label::Label;
+pub use radio_button::{RadioButton, RadioGroup, RadioSelectionChanged};
+pub use spin_box::{SpinBox, SpinBoxChanged};
 pub use stack::Stack;
+pub use text_area::TextArea;
 
 pub mod components {
     pub use super::button::components::*;
+    pub use super::check_box::components::*;
     pub use super::input_box::components::*;
+    pub use super::radio_button::components::*;
+    pub use super::spin_box::components::*;
+    pub use super::text_area::components::*;
 }
@@ -0,0 +1,335 @@
+use super::{
+    check_box::{spawn_indicator, spawn_toggle},
+    Base, Label,
+};
+use crate::{FocusScope, Widget, WidgetBuilder};
+use bevy::{ecs::world::EntityMut, prelude::*, ui::FocusPolicy};
+
+/// Container of `RadioButton`s, exactly one of which is selected
+pub struct RadioGroup {
+    base: Base,
+}
+
+pub struct RadioButton {
+    base: Base,
+    mark: Entity,
+}
+
+/// Sent when the user selects a different `RadioButton` in a group
+pub struct RadioSelectionChanged {
+    pub group: Entity,
+    pub selected: Entity,
+    pub value: String,
+}
+
+pub mod components {
+    use bevy::{
+        ecs::{
+            entity::{EntityMap, MapEntities, MapEntitiesError},
+            reflect::ReflectMapEntities,
+        },
+        prelude::*,
+    };
+
+    use crate::{CurrentFocus, FocusRequestEvent, InheritedDisabled};
+
+    use super::RadioSelectionChanged;
+
+    #[derive(Reflect, Default, Component)]
+    #[reflect(Component, MapEntities)]
+    pub struct RadioGroup {
+        /// Members of the group, in keyboard navigation order
+        pub members: Vec<Entity>,
+        /// Index of the selected member
+        pub selected: usize,
+    }
+
+    // This could be derived eventually
+    impl MapEntities for RadioGroup {
+        fn map_entities(&mut self, entity_map: &EntityMap) -> Result<(), MapEntitiesError> {
+            for member in self.members.iter_mut() {
+                *member = entity_map.get(*member)?;
+            }
+            Ok(())
+        }
+    }
+
+    #[derive(Reflect, Component)]
+    #[reflect(Component, MapEntities)]
+    pub struct RadioButton {
+        pub group: Entity,
+        pub mark: Entity,
+        pub value: String,
+    }
+
+    // I hate this
+    impl FromWorld for RadioButton {
+        fn from_world(_world: &mut bevy::prelude::World) -> Self {
+            Self {
+                group: Entity::new(u32::MAX),
+                mark: Entity::new(u32::MAX),
+                value: String::new(),
+            }
+        }
+    }
+
+    // This could be derived eventually
+    impl MapEntities for RadioButton {
+        fn map_entities(&mut self, entity_map: &EntityMap) -> Result<(), MapEntitiesError> {
+            self.group = entity_map.get(self.group)?;
+            self.mark = entity_map.get(self.mark)?;
+            Ok(())
+        }
+    }
+
+    fn select(
+        group_entity: Entity,
+        group: &mut RadioGroup,
+        index: usize,
+        value: &str,
+        event_writer: &mut EventWriter<RadioSelectionChanged>,
+    ) {
+        if group.selected != index {
+            group.selected = index;
+            event_writer.send(RadioSelectionChanged {
+                group: group_entity,
+                selected: group.members[index],
+                value: value.to_string(),
+            });
+        }
+    }
+
+    /// Selects a `RadioButton` when clicked, or activated with the keyboard (see `button_keyboard`)
+    pub fn radio_button_click(
+        query_button: Query<(Entity, &RadioButton, &Interaction), Changed<Interaction>>,
+        mut query_group: Query<&mut RadioGroup>,
+        mut event_writer: EventWriter<RadioSelectionChanged>,
+    ) {
+        for (entity, radio_button, interaction) in query_button.iter() {
+            if matches!(interaction, Interaction::Clicked) {
+                if let Ok(mut group) = query_group.get_mut(radio_button.group) {
+                    if let Some(index) = group.members.iter().position(|e| *e == entity) {
+                        select(
+                            radio_button.group,
+                            &mut group,
+                            index,
+                            &radio_button.value,
+                            &mut event_writer,
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    /// Moves the selection and the focus inside the group of the focused `RadioButton` with the arrow keys,
+    /// skipping disabled members
+    pub fn radio_button_keyboard(
+        keyboard_input: Res<Input<KeyCode>>,
+        current_focus: Res<CurrentFocus>,
+        query_button: Query<&RadioButton, Without<InheritedDisabled>>,
+        mut query_group: Query<&mut RadioGroup>,
+        mut event_writer: EventWriter<RadioSelectionChanged>,
+        mut focus_writer: EventWriter<FocusRequestEvent>,
+    ) {
+        let step = if keyboard_input.just_pressed(KeyCode::Up)
+            || keyboard_input.just_pressed(KeyCode::Left)
+        {
+            -1
+        } else if keyboard_input.just_pressed(KeyCode::Down)
+            || keyboard_input.just_pressed(KeyCode::Right)
+        {
+            1
+        } else {
+            return;
+        };
+        let focus = if let Some(focus) = current_focus.0 {
+            focus
+        } else {
+            return;
+        };
+        let radio_button = if let Ok(radio_button) = query_button.get(focus) {
+            radio_button
+        } else {
+            return;
+        };
+        let mut group = if let Ok(group) = query_group.get_mut(radio_button.group) {
+            group
+        } else {
+            return;
+        };
+        let len = group.members.len() as isize;
+        let index = if let Some(index) = group.members.iter().position(|e| *e == focus) {
+            index as isize
+        } else {
+            return;
+        };
+        for offset in 1..len {
+            let next = (index + step * offset).rem_euclid(len) as usize;
+            if let Ok(next_button) = query_button.get(group.members[next]) {
+                select(
+                    radio_button.group,
+                    &mut group,
+                    next,
+                    &next_button.value,
+                    &mut event_writer,
+                );
+                focus_writer.send(FocusRequestEvent {
+                    target: Some(group.members[next]),
+                });
+                break;
+            }
+        }
+    }
+
+    /// Keeps exactly one member of each group selected, and shows its mark
+    pub fn update_radio_marks(
+        mut query_group: Query<&mut RadioGroup, Changed<RadioGroup>>,
+        query_button: Query<&RadioButton>,
+        mut query_mark: Query<&mut Visible>,
+    ) {
+        for mut group in query_group.iter_mut() {
+            if group.members.is_empty() {
+                continue;
+            }
+            if group.selected >= group.members.len() {
+                group.selected = group.members.len() - 1;
+            }
+            for (index, member) in group.members.iter().enumerate() {
+                if let Ok(radio_button) = query_button.get(*member) {
+                    if let Ok(mut visible) = query_mark.get_mut(radio_button.mark) {
+                        visible.is_visible = index == group.selected;
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl RadioGroup {
+    /// Creates a group with a `RadioButton` for each value, the first one being selected
+    pub fn new(wb: &WidgetBuilder, flex_direction: FlexDirection, values: &[&str]) -> Self {
+        let group = Self {
+            base: Base::spawn(wb)
+                .insert_bundle(NodeBundle {
+                    style: Style {
+                        flex_direction,
+                        flex_shrink: 0.,
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                })
+                .insert(components::RadioGroup::default())
+                // The group handles the arrow keys itself
+                .insert(FocusScope {
+                    trap: false,
+                    directional: false,
+                }),
+        };
+        let buttons = values
+            .iter()
+            .map(|value| RadioButton::new(wb, group.root_id(), value).root_id())
+            .collect::<Vec<_>>();
+        group.push_children(&buttons).selected(0)
+    }
+
+    pub fn new_col(wb: &WidgetBuilder, values: &[&str]) -> Self {
+        Self::new(wb, FlexDirection::ColumnReverse, values)
+    }
+
+    pub fn new_row(wb: &WidgetBuilder, values: &[&str]) -> Self {
+        Self::new(wb, FlexDirection::Row, values)
+    }
+
+    pub fn selected(self, index: usize) -> Self {
+        let mut world = self.builder().world_mut();
+        let members = world
+            .entity(self.root_id())
+            .get::<components::RadioGroup>()
+            .unwrap()
+            .members
+            .clone();
+        for (i, member) in members.iter().enumerate() {
+            let mark = world
+                .entity(*member)
+                .get::<components::RadioButton>()
+                .unwrap()
+                .mark;
+            world
+                .entity_mut(mark)
+                .get_mut::<Visible>()
+                .unwrap()
+                .is_visible = i == index;
+        }
+        drop(world);
+        self.get_mut(|group: &mut components::RadioGroup| {
+            group.selected = index;
+        })
+    }
+
+    pub fn with_each_button(self, mut f: impl FnMut(EntityMut)) -> Self {
+        let mut world = self.builder().world_mut();
+        let members = world
+            .entity(self.root_id())
+            .get::<components::RadioGroup>()
+            .unwrap()
+            .members
+            .clone();
+        for member in members {
+            f(world.entity_mut(member));
+        }
+        drop(world);
+        self
+    }
+}
+
+impl Widget for RadioGroup {
+    fn builder(&self) -> &WidgetBuilder {
+        self.base.builder()
+    }
+
+    fn root_id(&self) -> Entity {
+        self.base.root_id()
+    }
+}
+
+impl RadioButton {
+    /// Creates a labelled `RadioButton` and adds it to the members of `group`.
+    /// It still has to be inserted in the UI tree, e.g. as a child of the group.
+    pub fn new(wb: &WidgetBuilder, group: Entity, value: &str) -> Self {
+        let (circle, mark) = spawn_indicator(wb, "•");
+        let label = Label::new(wb, value).insert(FocusPolicy::Pass).root_id();
+        let base = spawn_toggle(wb, circle)
+            .insert(components::RadioButton {
+                group,
+                mark,
+                value: value.to_string(),
+            })
+            .push_children(&[label]);
+        let root = base.root_id();
+        wb.world_mut()
+            .entity_mut(group)
+            .get_mut::<components::RadioGroup>()
+            .expect("RadioButton group should be a RadioGroup")
+            .members
+            .push(root);
+        Self { base, mark }.mark(|mut mark| {
+            mark.get_mut::<Visible>().unwrap().is_visible = false;
+        })
+    }
+
+    pub fn mark(self, f: impl FnOnce(EntityMut)) -> Self {
+        f(self.builder().world_mut().entity_mut(self.mark));
+        self
+    }
+}
+
+impl Widget for RadioButton {
+    fn builder(&self) -> &WidgetBuilder {
+        self.base.builder()
+    }
+
+    fn root_id(&self) -> Entity {
+        self.base.root_id()
+    }
+}
@@ -0,0 +1,301 @@
+use super::{Base, Button, InputBox, Label};
+use crate::{Focusable, Widget, WidgetBuilder};
+use bevy::{ecs::world::EntityMut, prelude::*};
+
+/// Numeric field made of an `InputBox` and two `Button`s that decrement and increment its value.
+/// The value can also be stepped with Up/Down and the mouse wheel while the `InputBox` is focused.
+/// Typed text is parsed, clamped and reformatted when it is submitted or loses the focus.
+pub struct SpinBox {
+    base: Base,
+    input_box: Entity,
+}
+
+/// Sent when the value of a `SpinBox` is changed by the user
+pub struct SpinBoxChanged {
+    pub source: Entity,
+    pub value: f64,
+}
+
+pub mod components {
+    use bevy::{
+        ecs::{
+            entity::{EntityMap, MapEntities, MapEntitiesError},
+            reflect::ReflectMapEntities,
+        },
+        input::mouse::MouseWheel,
+        prelude::*,
+    };
+
+    use crate::{
+        widgets::{components::InputBox, InputBoxReturnEvent, InputBoxSetEvent},
+        FocusLost, Focusable, InheritedDisabled,
+    };
+
+    use super::SpinBoxChanged;
+
+    #[derive(Reflect, Component)]
+    #[reflect(Component, MapEntities)]
+    pub struct SpinBox {
+        pub input_box: Entity,
+        pub decrement: Entity,
+        pub increment: Entity,
+        pub value: f64,
+        pub min: f64,
+        pub max: f64,
+        pub step: f64,
+        /// Number of decimals, the value is an integer if 0
+        pub precision: usize,
+    }
+
+    impl SpinBox {
+        /// Rounds `value` to the precision and clamps it between `min` and `max`
+        pub fn clamp(&self, value: f64) -> f64 {
+            let scale = 10f64.powi(self.precision as i32);
+            ((value * scale).round() / scale)
+                .max(self.min)
+                .min(self.max)
+        }
+
+        pub fn format(&self) -> String {
+            format!("{:.*}", self.precision, self.value)
+        }
+
+        /// The value of an integer `SpinBox`
+        pub fn as_i64(&self) -> i64 {
+            self.value.round() as i64
+        }
+
+        /// Sets the value from the user, always marking the `SpinBox` as changed so that
+        /// its text is reformatted
+        fn set(
+            &mut self,
+            source: Entity,
+            value: f64,
+            event_writer: &mut EventWriter<SpinBoxChanged>,
+        ) {
+            let value = self.clamp(value);
+            if (value - self.value).abs() > f64::EPSILON {
+                event_writer.send(SpinBoxChanged { source, value });
+            }
+            self.value = value;
+        }
+    }
+
+    // I hate this
+    impl FromWorld for SpinBox {
+        fn from_world(_world: &mut bevy::prelude::World) -> Self {
+            Self {
+                input_box: Entity::new(u32::MAX),
+                decrement: Entity::new(u32::MAX),
+                increment: Entity::new(u32::MAX),
+                value: 0.,
+                min: f64::MIN,
+                max: f64::MAX,
+                step: 1.,
+                precision: 0,
+            }
+        }
+    }
+
+    // This could be derived eventually
+    impl MapEntities for SpinBox {
+        fn map_entities(&mut self, entity_map: &EntityMap) -> Result<(), MapEntitiesError> {
+            self.input_box = entity_map.get(self.input_box)?;
+            self.decrement = entity_map.get(self.decrement)?;
+            self.increment = entity_map.get(self.increment)?;
+            Ok(())
+        }
+    }
+
+    /// Value typed in the `InputBox` of a `SpinBox`, if it is a number
+    fn typed_value(
+        spin_box: &SpinBox,
+        query_input_box: &Query<&InputBox>,
+        query_text: &Query<&Text>,
+    ) -> Option<f64> {
+        let input_box = query_input_box.get(spin_box.input_box).ok()?;
+        let text = query_text.get(input_box.text).ok()?;
+        text.sections[0]
+            .value
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .filter(|value| value.is_finite())
+    }
+
+    /// Steps the value with the buttons, and with Up/Down or the mouse wheel while the `InputBox`
+    /// is focused. Text that was typed but not submitted yet is stepped from.
+    #[allow(clippy::too_many_arguments)]
+    pub fn spin_box_step(
+        mut query_spin_box: Query<(Entity, &mut SpinBox), Without<InheritedDisabled>>,
+        query_button: Query<&Interaction, Changed<Interaction>>,
+        query_focusable: Query<&Focusable>,
+        query_input_box: Query<&InputBox>,
+        query_text: Query<&Text>,
+        keyboard_input: Res<Input<KeyCode>>,
+        mut mouse_wheel_events: EventReader<MouseWheel>,
+        mut event_writer: EventWriter<SpinBoxChanged>,
+    ) {
+        let wheel = mouse_wheel_events.iter().map(|event| event.y).sum::<f32>();
+        for (entity, mut spin_box) in query_spin_box.iter_mut() {
+            let clicked = |button| matches!(query_button.get(button), Ok(Interaction::Clicked));
+            let mut steps = 0;
+            if clicked(spin_box.increment) {
+                steps += 1;
+            }
+            if clicked(spin_box.decrement) {
+                steps -= 1;
+            }
+            if matches!(
+                query_focusable.get(spin_box.input_box),
+                Ok(Focusable::Focused)
+            ) {
+                if keyboard_input.just_pressed(KeyCode::Up) || wheel > 0. {
+                    steps += 1;
+                }
+                if keyboard_input.just_pressed(KeyCode::Down) || wheel < 0. {
+                    steps -= 1;
+                }
+            }
+            if steps != 0 {
+                let value =
+                    typed_value(&spin_box, &query_input_box, &query_text).unwrap_or(spin_box.value);
+                let step = spin_box.step;
+                spin_box.set(entity, value + steps as f64 * step, &mut event_writer);
+            }
+        }
+    }
+
+    /// Parses, clamps and reformats the text of the `InputBox` when it is submitted or loses
+    /// the focus. Text that is not a number is reverted to the current value.
+    pub fn spin_box_commit(
+        mut return_events: EventReader<InputBoxReturnEvent>,
+        mut focus_lost_events: EventReader<FocusLost>,
+        mut query_spin_box: Query<(Entity, &mut SpinBox)>,
+        query_input_box: Query<&InputBox>,
+        query_text: Query<&Text>,
+        mut event_writer: EventWriter<SpinBoxChanged>,
+    ) {
+        let committed = return_events
+            .iter()
+            .map(|event| event.source)
+            .chain(focus_lost_events.iter().map(|event| event.entity))
+            .collect::<Vec<_>>();
+        if committed.is_empty() {
+            return;
+        }
+        for (entity, mut spin_box) in query_spin_box.iter_mut() {
+            if committed.contains(&spin_box.input_box) {
+                let value =
+                    typed_value(&spin_box, &query_input_box, &query_text).unwrap_or(spin_box.value);
+                spin_box.set(entity, value, &mut event_writer);
+            }
+        }
+    }
+
+    /// Shows the formatted value in the `InputBox`
+    pub fn update_spin_box_text(
+        query_spin_box: Query<&SpinBox, Changed<SpinBox>>,
+        query_input_box: Query<&InputBox>,
+        query_text: Query<&Text>,
+        mut event_writer: EventWriter<InputBoxSetEvent>,
+    ) {
+        for spin_box in query_spin_box.iter() {
+            let input_box = query_input_box.get(spin_box.input_box).unwrap();
+            let text = query_text.get(input_box.text).unwrap();
+            let formatted = spin_box.format();
+            if text.sections[0].value != formatted {
+                event_writer.send(InputBoxSetEvent {
+                    target: spin_box.input_box,
+                    text: formatted,
+                });
+            }
+        }
+    }
+}
+
+impl SpinBox {
+    pub fn new(wb: &WidgetBuilder) -> Self {
+        let input_box = InputBox::new(wb).root_id();
+        let decrement = spin_button(wb, "-");
+        let increment = spin_button(wb, "+");
+        let base = Base::spawn(wb)
+            .insert_bundle(NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Row,
+                    flex_shrink: 0.,
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .insert(components::SpinBox {
+                input_box,
+                decrement,
+                increment,
+                value: 0.,
+                min: f64::MIN,
+                max: f64::MAX,
+                step: 1.,
+                precision: 0,
+            })
+            .push_children(&[input_box, decrement, increment]);
+        Self { base, input_box }.precision(0)
+    }
+
+    pub fn value(self, value: f64) -> Self {
+        self.get_mut(|spin_box: &mut components::SpinBox| {
+            spin_box.value = spin_box.clamp(value);
+        })
+    }
+
+    pub fn range(self, min: f64, max: f64) -> Self {
+        self.get_mut(|spin_box: &mut components::SpinBox| {
+            spin_box.min = min;
+            spin_box.max = max;
+            spin_box.value = spin_box.clamp(spin_box.value);
+        })
+    }
+
+    pub fn step(self, step: f64) -> Self {
+        self.get_mut(|spin_box: &mut components::SpinBox| {
+            spin_box.step = step;
+        })
+    }
+
+    /// Sets the number of decimals, and the characters that can be typed accordingly
+    pub fn precision(self, precision: usize) -> Self {
+        let allowed = if precision == 0 { "0-9-" } else { "0-9.-" };
+        self.get_mut(|spin_box: &mut components::SpinBox| {
+            spin_box.precision = precision;
+            spin_box.value = spin_box.clamp(spin_box.value);
+        })
+        .input_box(|mut input_box| {
+            input_box.insert(super::components::InputFilter::allowed(allowed));
+        })
+    }
+
+    pub fn input_box(self, f: impl FnOnce(EntityMut)) -> Self {
+        f(self.builder().world_mut().entity_mut(self.input_box));
+        self
+    }
+}
+
+/// Spawns a `Button` that steps the value. It is not focusable, so that the focus stays in
+/// the `InputBox`.
+fn spin_button(wb: &WidgetBuilder, label: &str) -> Entity {
+    Button::new(Label::new(wb, label).set_font_size(16.))
+        .with_root(|mut root| {
+            root.remove::<Focusable>();
+        })
+        .root_id()
+}
+
+impl Widget for SpinBox {
+    fn builder(&self) -> &WidgetBuilder {
+        self.base.builder()
+    }
+
+    fn root_id(&self) -> Entity {
+        self.base.root_id()
+    }
+}
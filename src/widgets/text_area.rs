@@ -0,0 +1,112 @@
+use super::InputBox;
+use crate::{Widget, WidgetBuilder};
+use bevy::{ecs::world::EntityMut, prelude::*};
+
+/// Multi-line `InputBox`: Enter inserts a new line and the `SubmitShortcut` sends the
+/// `InputBoxReturnEvent`. The text is wrapped to the width of the node, and scrolled vertically
+/// to keep the caret visible.
+pub struct TextArea {
+    input_box: InputBox,
+}
+
+pub mod components {
+    use bevy::prelude::*;
+    use serde::{Deserialize, Serialize};
+
+    use crate::widgets::components::InputBox;
+
+    /// Keys that submit the content of a `TextArea`, in addition to Enter
+    #[derive(Reflect, Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+    #[reflect_value(PartialEq, Serialize, Deserialize)]
+    pub enum SubmitShortcut {
+        CtrlEnter,
+        ShiftEnter,
+        Disabled,
+    }
+
+    impl Default for SubmitShortcut {
+        fn default() -> Self {
+            Self::CtrlEnter
+        }
+    }
+
+    impl SubmitShortcut {
+        pub fn is_pressed(self, ctrl: bool, shift: bool) -> bool {
+            match self {
+                Self::CtrlEnter => ctrl,
+                Self::ShiftEnter => shift,
+                Self::Disabled => false,
+            }
+        }
+    }
+
+    /// Makes an `InputBox` multi-line
+    #[derive(Reflect, Default, Component)]
+    #[reflect(Component)]
+    pub struct TextArea {
+        pub submit: SubmitShortcut,
+    }
+
+    /// Wraps the text to the width of the `TextArea`
+    pub fn text_area_wrap(
+        query_area: Query<(&InputBox, &Node), (With<TextArea>, Changed<Node>)>,
+        mut query_style: Query<&mut Style>,
+    ) {
+        for (input_box, node) in query_area.iter() {
+            // The text has a 2px margin inside the box
+            let width = Val::Px((node.size.x - 4.).max(0.));
+            let mut style = query_style.get_mut(input_box.text).unwrap();
+            if style.max_size.width != width {
+                style.max_size.width = width;
+            }
+        }
+    }
+}
+
+impl TextArea {
+    pub fn new(wb: &WidgetBuilder) -> Self {
+        let input_box = InputBox::new(wb)
+            .get_mut(|style: &mut Style| {
+                style.size = Size::new(Val::Px(200.), Val::Px(100.));
+            })
+            .insert(components::TextArea::default())
+            .text(|mut text| {
+                text.get_mut::<Style>().unwrap().size.height = Val::Undefined;
+            });
+        Self { input_box }
+    }
+
+    pub fn submit(self, shortcut: components::SubmitShortcut) -> Self {
+        self.get_mut(|text_area: &mut components::TextArea| {
+            text_area.submit = shortcut;
+        })
+    }
+
+    pub fn placeholder(self, placeholder: &str) -> Self {
+        Self {
+            input_box: self.input_box.placeholder(placeholder),
+        }
+    }
+
+    pub fn text(self, f: impl FnOnce(EntityMut)) -> Self {
+        Self {
+            input_box: self.input_box.text(f),
+        }
+    }
+
+    pub fn caret(self, f: impl FnOnce(EntityMut)) -> Self {
+        Self {
+            input_box: self.input_box.caret(f),
+        }
+    }
+}
+
+impl Widget for TextArea {
+    fn builder(&self) -> &WidgetBuilder {
+        self.input_box.builder()
+    }
+
+    fn root_id(&self) -> Entity {
+        self.input_box.root_id()
+    }
+}